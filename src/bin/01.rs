@@ -1,4 +1,4 @@
-advent_of_code::solution!(1);
+advent_of_code::solution!(1, parse: parse_list);
 
 pub fn parse_list(input: &str) -> (Vec<usize>, Vec<usize>) {
     // First, we split the input into lines
@@ -27,11 +27,7 @@ pub fn part_one(input: &str) -> Option<usize> {
     // And find the absolute distance between the two values
     let distances = zipped.map(|(left, right)| {
         // Since we are dealing with unsigned integers, we find which one is largest and subtract the other
-        if left > right {
-            left - right
-        } else {
-            right - left
-        }
+        left.abs_diff(*right)
     });
     // Finally, we sum all the distances
     let sum: usize = distances.sum();
@@ -1,106 +1,52 @@
-advent_of_code::solution!(2);
+use std::ops::RangeInclusive;
 
-pub fn parse_input(input: &str) -> Vec<Vec<usize>> {
-    // First, we split the input into lines
-    let lines = input.lines();
-    // Then we split each line into a list of usize
-    let split = lines.map(|line| {
-        line.split_whitespace()
-            .map(|value| value.parse().unwrap())
-            .collect()
-    });
-    split.collect()
-}
-
-pub fn all_increasing(report: &[usize]) -> bool {
-    // We need to iterate through the report and make sure that
-    // They are all increasing
-    let mut iter = report.iter();
-    let mut last = iter.next().unwrap();
-
-    for value in iter {
-        if value <= last {
-            return false;
-        }
-        last = value;
-    }
-    true
-}
-
-pub fn all_decreasing(report: &[usize]) -> bool {
-    // We need to iterate through the report and make sure that
-    // They are all decreasing
-    let mut iter = report.iter();
-    let mut last = iter.next().unwrap();
+use advent_of_code::monotonic::{MonotonicChecker, Repair};
+use advent_of_code::viz;
 
-    for value in iter {
-        if value >= last {
-            return false;
-        }
-        last = value;
-    }
-    true
-}
+advent_of_code::solution!(2, parse: parse_input);
 
-pub fn all_diff_less_than_three(report: &[usize]) -> bool {
-    // We need to iterate through the report and make sure that
-    // The difference between each value is less than or equal to 3
-    let mut iter = report.iter();
-    let mut last = iter.next().unwrap();
+/// Levels have to change by at least one and at most three between neighbours.
+const SAFE_STEPS: RangeInclusive<i64> = 1..=3;
 
-    for value in iter {
-        if value > last && value - last > 3 {
-            return false;
-        }
-        if value < last && last - value > 3 {
-            return false;
-        }
-        last = value;
-    }
-    true
+pub fn parse_input(input: &str) -> Vec<Vec<i64>> {
+    input
+        .lines()
+        .map(|line| {
+            line.split_whitespace()
+                .map(|value| value.parse().unwrap())
+                .collect()
+        })
+        .collect()
 }
 
-pub fn report_is_safe(report: &[usize]) -> bool {
-    // We need to iterate through the report and make sure that
-    // They are either all increasing or all decreasing
-    // And that the difference between each value is less than or equal to 3
-    all_diff_less_than_three(report) && (all_increasing(report) || all_decreasing(report))
+/// How to make each report safe by removing up to `removals` levels, `None` for unsafe reports.
+fn repair_reports(input: &str, removals: usize) -> Vec<Option<Repair>> {
+    let checker = MonotonicChecker::new(SAFE_STEPS).with_removals(removals);
+    parse_input(input)
+        .iter()
+        .map(|report| {
+            let repair = checker.repair(report);
+            if let Some(Repair { trend, removed }) =
+                repair.as_ref().filter(|r| !r.removed.is_empty())
+            {
+                viz!("{report:?} is {trend:?} without the levels at {removed:?}");
+            }
+            repair
+        })
+        .collect()
 }
 
-pub fn report_is_safe_with_removal(report: &[usize]) -> bool {
-    // We need to iterate through the report and make sure that
-    // They are either all increasing or all decreasing
-    // And that the difference between each value is less than or equal to 3
-    // And that removing any value will still result in a safe report
-    if report_is_safe(report) {
-        return true;
-    }
-    // Else we need to make new reports with each value removed
-    // So we can loop through the indexes and remove the value at that index
-    for (index, _) in report.iter().enumerate() {
-        let mut new_report = report.to_vec();
-        new_report.remove(index);
-        if report_is_safe(&new_report) {
-            return true;
-        }
-    }
-    false
+/// Number of reports that are safe after removing up to `removals` levels.
+pub fn count_safe_reports(input: &str, removals: usize) -> usize {
+    repair_reports(input, removals).iter().flatten().count()
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let reports = parse_input(input);
-    let safe_reports = reports.iter().filter(|report| report_is_safe(report));
-    let count = safe_reports.count();
-    Some(count as u32)
+pub fn part_one(input: &str) -> Option<usize> {
+    Some(count_safe_reports(input, 0))
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let reports = parse_input(input);
-    let safe_reports = reports
-        .iter()
-        .filter(|report| report_is_safe_with_removal(report));
-    let count = safe_reports.count();
-    Some(count as u32)
+pub fn part_two(input: &str) -> Option<usize> {
+    Some(count_safe_reports(input, 1))
 }
 
 #[cfg(test)]
@@ -118,4 +64,24 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(4));
     }
+
+    #[test]
+    fn test_repair_reports() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let removed: Vec<Option<Vec<usize>>> = repair_reports(&input, 1)
+            .into_iter()
+            .map(|repair| repair.map(|repair| repair.removed))
+            .collect();
+        assert_eq!(
+            removed,
+            [
+                Some(vec![]),
+                None,
+                None,
+                Some(vec![2]),
+                Some(vec![3]),
+                Some(vec![])
+            ]
+        );
+    }
 }
@@ -1,3 +1,4 @@
+use advent_of_code::vm::{self, Cpu, Flow, InstructionSet};
 use regex::Regex;
 
 advent_of_code::solution!(3);
@@ -19,6 +20,7 @@ pub fn parse_input_one(input: &str) -> Vec<(usize, usize)> {
     result
 }
 
+#[derive(Clone, Debug)]
 enum Instructions {
     // This enables future multiplications
     Do,
@@ -28,12 +30,11 @@ enum Instructions {
     Multiply(usize, usize),
 }
 
-struct Machine {
-    instructions: Vec<Instructions>,
-    multiplication_enabled: bool,
-    accumulator: usize,
-    index: usize,
-}
+/// Registers of the machine.
+const ACCUMULATOR: usize = 0;
+const MULTIPLICATION_ENABLED: usize = 1;
+
+struct Machine;
 
 impl Machine {
     fn parse_instructions(input: &str) -> Vec<Instructions> {
@@ -55,34 +56,33 @@ impl Machine {
         }
         result
     }
-    fn new(instructions: Vec<Instructions>) -> Self {
-        Self {
-            instructions,
-            accumulator: 0,
-            index: 0,
-            multiplication_enabled: true,
-        }
+
+    fn new(instructions: Vec<Instructions>) -> vm::Machine<Self> {
+        let mut machine = vm::Machine::new(Self, instructions, 2);
+        machine.cpu.registers[MULTIPLICATION_ENABLED] = 1;
+        machine
     }
+}
 
-    fn run(&mut self) -> usize {
-        while self.index < self.instructions.len() {
-            match self.instructions[self.index] {
-                Instructions::Do => {
-                    self.multiplication_enabled = true;
-                }
-                Instructions::Dont => {
-                    self.multiplication_enabled = false;
-                }
-                Instructions::Multiply(x, y) => {
-                    if !self.multiplication_enabled {
-                    } else {
-                        self.accumulator += x * y;
-                    }
+impl InstructionSet for Machine {
+    type Instruction = Instructions;
+    type Word = usize;
+
+    fn execute(&self, instruction: &Instructions, cpu: &mut Cpu<usize>) -> Flow {
+        match instruction {
+            Instructions::Do => {
+                cpu.registers[MULTIPLICATION_ENABLED] = 1;
+            }
+            Instructions::Dont => {
+                cpu.registers[MULTIPLICATION_ENABLED] = 0;
+            }
+            Instructions::Multiply(x, y) => {
+                if cpu.registers[MULTIPLICATION_ENABLED] == 1 {
+                    cpu.registers[ACCUMULATOR] += x * y;
                 }
             }
-            self.index += 1;
         }
-        self.accumulator
+        Flow::Next
     }
 }
 
@@ -91,10 +91,11 @@ pub fn part_one(input: &str) -> Option<usize> {
     Some(parsed_tuples.iter().map(|(x, y)| x * y).sum::<usize>())
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
+pub fn part_two(input: &str) -> Option<usize> {
     let instructions = Machine::parse_instructions(input);
     let mut machine = Machine::new(instructions);
-    Some(machine.run() as u32)
+    machine.run();
+    Some(machine.cpu.registers[ACCUMULATOR])
 }
 
 #[cfg(test)]
@@ -1,117 +1,59 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use advent_of_code::digraph::DiGraph;
 
-advent_of_code::solution!(5);
+advent_of_code::solution!(5, parse: SafetyManual::parse_input, variants {
+    2: "toposort" => part_two_toposort,
+});
 
 struct SafetyManual {
     updates: Vec<Vec<usize>>,
-    graph: HashMap<usize, HashSet<usize>>,
+    rules: DiGraph<usize>,
 }
 
 impl SafetyManual {
     fn parse_input(input: &str) -> Self {
         // The two sections are separated with a blank line
         let mut sections = input.split("\n\n");
-        let adj_list: Vec<(usize, usize)> = sections
-            .next()
-            .unwrap()
-            .lines()
-            .map(|line| {
-                let mut parts = line.split("|");
-                let from = parts.next().unwrap().parse().unwrap();
-                let to = parts.next().unwrap().parse().unwrap();
-                (from, to)
-            })
-            .collect();
+        let rules = DiGraph::from_edges(sections.next().unwrap().lines().map(|line| {
+            let (from, to) = line.split_once('|').unwrap();
+            (from.parse().unwrap(), to.parse().unwrap())
+        }));
         let updates = sections
             .next()
             .unwrap()
             .lines()
             .map(|line| line.split(",").map(|part| part.parse().unwrap()).collect())
             .collect();
-        let graph = SafetyManual::create_graph(adj_list.clone());
-        SafetyManual { updates, graph }
+        SafetyManual { updates, rules }
     }
 
     fn is_sorted(&self, update: &[usize]) -> bool {
-        // We need to loop through every item in the update list
-        let mut queue: VecDeque<usize> = update.iter().cloned().collect();
-        while let Some(item) = queue.pop_front() {
-            // If the item isn't in the graph, it doesn't depend on any other item
-            if !self.graph.contains_key(&item) {
-                continue;
-            }
-            // If it does not depend on any other item in the list, add it to the sorted list
-            for remaining_item in &queue.clone() {
-                if !self.graph.contains_key(remaining_item) {
-                    println!("Remaining item missing in graph: {:?}", remaining_item);
-                    continue;
-                }
-                if self.graph[remaining_item].contains(&item) {
-                    return false;
-                }
-            }
-        }
-        true
+        self.rules.is_ordered(update)
     }
 
-    fn create_graph(adj_list: Vec<(usize, usize)>) -> HashMap<usize, HashSet<usize>> {
-        let mut graph = HashMap::new();
-        for &(from, to) in &adj_list {
-            graph.entry(from).or_insert_with(HashSet::new).insert(to);
-            // If the to node is not in the graph, add it
-            graph.entry(to).or_insert_with(HashSet::new);
-        }
-        graph
+    /// The rules cover every pair of pages in an update, so they can be compared directly.
+    fn sort(&self, update: &[usize]) -> Vec<usize> {
+        let mut sorted = update.to_vec();
+        sorted.sort_by(self.rules.comparator());
+        sorted
     }
 
-    fn sort(&mut self, update: &[usize]) -> Vec<usize> {
-        let mut sorted = Vec::new();
-        // For each item, if it does not depend on any other item in the list, add it to the queue
-        let mut queue: VecDeque<usize> = update.iter().cloned().collect();
-        while let Some(item) = queue.pop_front() {
-            // If it does not depend on any other item in the list, add it to the sorted list
-            let mut is_in_order = true;
-            for remaining_item in &queue.clone() {
-                if !self.graph.contains_key(remaining_item) {
-                    self.graph.insert(*remaining_item, HashSet::new());
-                }
-                if self.graph[remaining_item].contains(&item) {
-                    queue.push_back(item);
-                    queue.retain(|&x| x != *remaining_item);
-                    queue.push_front(*remaining_item);
-                    is_in_order = false;
-                    break;
-                }
-            }
-            if is_in_order {
-                sorted.push(item);
-            }
-        }
-        // Sorted should be the same length as the update list
-        if sorted.len() != update.len() {
-            panic!("Could not sort the list");
+    /// Orders the pages through the rules between them, without relying on every pair being covered.
+    fn sort_topologically(&self, update: &[usize]) -> Vec<usize> {
+        match self.rules.order_subset(update) {
+            Ok(sorted) => sorted,
+            Err(cycle) => panic!(
+                "Could not sort the list, the rules contain the cycle {:?}",
+                cycle.0
+            ),
         }
-        sorted
     }
 
-    fn get_unordered_updates(&self) -> Vec<Vec<usize>> {
-        let mut unordered_updates = Vec::new();
-        for update in &self.updates {
-            if !self.is_sorted(update) {
-                unordered_updates.push(update.clone());
-            }
-        }
-        unordered_updates
+    fn get_unordered_updates(&self) -> impl Iterator<Item = &Vec<usize>> {
+        self.updates.iter().filter(|update| !self.is_sorted(update))
     }
 
-    fn get_ordered_updates(&self) -> Vec<Vec<usize>> {
-        let mut ordered_updates = Vec::new();
-        for update in &self.updates {
-            if self.is_sorted(update) {
-                ordered_updates.push(update.clone());
-            }
-        }
-        ordered_updates
+    fn get_ordered_updates(&self) -> impl Iterator<Item = &Vec<usize>> {
+        self.updates.iter().filter(|update| self.is_sorted(update))
     }
 }
 
@@ -125,12 +67,10 @@ pub fn part_one(input: &str) -> Option<usize> {
     // Find the middle item
     // Return the sum of the middle item
     let manual = SafetyManual::parse_input(input);
-    let ordered_updates = manual.get_ordered_updates();
-    let middle_item = ordered_updates
-        .iter()
+    let sum = manual
+        .get_ordered_updates()
         .map(|update| *get_middle_item(update))
-        .collect::<Vec<usize>>();
-    let sum: usize = middle_item.iter().sum();
+        .sum();
     Some(sum)
 }
 
@@ -138,14 +78,20 @@ pub fn part_two(input: &str) -> Option<usize> {
     // Get all the sorted updates
     // Find the middle item
     // Return the sum of the middle item
-    let mut manual = SafetyManual::parse_input(input);
-    let ordered_updates = manual.get_unordered_updates();
-    let middle_item = ordered_updates
-        .iter()
-        .map(|update| manual.sort(update))
-        .map(|update| *get_middle_item(&update))
-        .collect::<Vec<usize>>();
-    let sum: usize = middle_item.iter().sum();
+    let manual = SafetyManual::parse_input(input);
+    let sum = manual
+        .get_unordered_updates()
+        .map(|update| *get_middle_item(&manual.sort(update)))
+        .sum();
+    Some(sum)
+}
+
+pub fn part_two_toposort(input: &str) -> Option<usize> {
+    let manual = SafetyManual::parse_input(input);
+    let sum = manual
+        .get_unordered_updates()
+        .map(|update| *get_middle_item(&manual.sort_topologically(update)))
+        .sum();
     Some(sum)
 }
 
@@ -164,4 +110,10 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(123));
     }
+
+    #[test]
+    fn test_part_two_toposort() {
+        let result = part_two_toposort(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(123));
+    }
 }
@@ -1,16 +1,16 @@
-use std::collections::VecDeque;
+use std::fmt;
 
-// TODO: This is super slow, it was completely naiive because I was having an issue with the u32 that the template expected
+use advent_of_code::viz;
 
 advent_of_code::solution!(7);
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 enum Expression {
     Value(usize),
     Operator(Operators),
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 enum Operators {
     Add,
     Multiply,
@@ -25,41 +25,80 @@ impl Operators {
             Operators::Concatenate => {
                 // This should work the same as a string concatenation
                 // but for numbers
-                let mut a = a;
-                let mut b_ref = b;
-                while b_ref > 0 {
-                    a *= 10;
-                    b_ref /= 10;
+                a * Self::concat_factor(b) + b
+            }
+        }
+    }
+
+    /// Finds `a` such that `a <op> b == result`, if there is one.
+    /// Operators are evaluated left to right, so `b` is always the last operand.
+    ///
+    /// A multiplication by zero has no single `a`, see [`Equation::search`].
+    fn undo(&self, result: usize, b: usize) -> Option<usize> {
+        match self {
+            Operators::Add => result.checked_sub(b),
+            Operators::Multiply => {
+                if b != 0 && result.is_multiple_of(b) {
+                    Some(result / b)
+                } else {
+                    None
                 }
-                a + b
             }
+            Operators::Concatenate => {
+                // the result must end with the digits of `b`.
+                let factor = Self::concat_factor(b);
+                if result % factor == b {
+                    Some(result / factor)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// The power of ten that shifts a number left by the amount of digits in `b`.
+    /// Zero has one digit as well.
+    fn concat_factor(b: usize) -> usize {
+        let mut factor = 10;
+        let mut b_ref = b / 10;
+        while b_ref > 0 {
+            factor *= 10;
+            b_ref /= 10;
         }
+        factor
     }
 }
 
-#[derive(Clone, PartialEq)]
+impl fmt::Display for Operators {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operators::Add => write!(f, "+"),
+            Operators::Multiply => write!(f, "*"),
+            Operators::Concatenate => write!(f, "||"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 struct Solution {
     operators: Vec<Expression>,
 }
 
 impl Solution {
-    fn add_expression(&mut self, expression: Expression) {
-        self.operators.push(expression);
-    }
     fn evaluate(&self) -> usize {
-        // These are infix operators
-        let mut ops: VecDeque<Expression> = self.operators.clone().into();
-        let mut acc = match ops.pop_front().unwrap() {
-            Expression::Value(x) => x,
+        // These are infix operators, evaluated left to right
+        let mut ops = self.operators.iter();
+        let mut acc = match ops.next().unwrap() {
+            Expression::Value(x) => *x,
             _ => panic!("First element must be a value"),
         };
-        while let Some(expr) = ops.pop_front() {
+        while let Some(expr) = ops.next() {
             match expr {
                 Expression::Operator(op) => {
                     acc = op.apply(
                         acc,
-                        match ops.pop_front().unwrap() {
-                            Expression::Value(x) => x,
+                        match ops.next().unwrap() {
+                            Expression::Value(x) => *x,
                             _ => panic!("Value must follow operator"),
                         },
                     )
@@ -71,14 +110,28 @@ impl Solution {
     }
 }
 
+impl fmt::Display for Solution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, expr) in self.operators.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            match expr {
+                Expression::Value(x) => write!(f, "{x}")?,
+                Expression::Operator(op) => write!(f, "{op}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
 struct Equation {
     result: usize,
-    operators: Vec<Operators>,
     operands: Vec<usize>,
 }
 
 impl Equation {
-    fn from_line(line: &str, operators: Vec<Operators>) -> Equation {
+    fn from_line(line: &str) -> Equation {
         // Split based on ':', the left side is the result, the right side is the operands
         let parts: Vec<&str> = line.split(":").collect();
         let result = parts[0].parse::<usize>().unwrap();
@@ -86,78 +139,82 @@ impl Equation {
             .split_whitespace()
             .map(|x| x.parse::<usize>().unwrap())
             .collect();
-        Equation {
-            result,
-            operands,
-            operators,
-        }
+        Equation { result, operands }
     }
 
-    fn get_solutions(&self) -> Vec<Solution> {
-        // Apply the operands left to right
-        // Try all possible combinations of operators
-        // If the result is found, add it to the list
-        let mut operands: VecDeque<usize> = self.operands.clone().into();
-        let mut accumulators: Vec<Solution> = vec![Solution {
-            operators: vec![Expression::Value(operands.pop_front().unwrap())],
-        }];
-        for operand in operands {
-            let mut new_accumulators: Vec<Solution> = vec![];
-            for acc in accumulators {
-                for operator in self.operators.iter() {
-                    let mut new_acc = acc.clone();
-                    new_acc.add_expression(Expression::Operator(operator.clone()));
-                    new_acc.add_expression(Expression::Value(operand));
-                    new_accumulators.push(new_acc);
-                }
-            }
-            accumulators = new_accumulators;
+    /// Works backwards from `target` through the first `count` operands, undoing one operator at a time.
+    /// Returns the operators that were applied (in order) if the operands can produce `target`.
+    fn search<'a>(
+        &self,
+        target: usize,
+        count: usize,
+        operators: &'a [Operators],
+    ) -> Option<Vec<&'a Operators>> {
+        match count {
+            0 => return None,
+            1 => return (self.operands[0] == target).then(Vec::new),
+            _ => {}
         }
-        accumulators
-    }
 
-    fn is_solvable(&self) -> bool {
-        // Check if the equation is solvable
-        // If the result is in the operands, it is solvable
-        let solutions = self.get_solutions();
-        for solution in solutions {
-            if solution.evaluate() == self.result {
-                // solution.print();
-                return true;
+        let last = self.operands[count - 1];
+        operators.iter().find_map(|operator| {
+            if *operator == Operators::Multiply && last == 0 && target == 0 {
+                // anything times zero is zero, so the operands before can be combined in any way.
+                let mut applied = vec![&operators[0]; count - 2];
+                applied.push(operator);
+                return Some(applied);
             }
+            let previous = operator.undo(target, last)?;
+            let mut applied = self.search(previous, count - 1, operators)?;
+            applied.push(operator);
+            Some(applied)
+        })
+    }
+
+    /// Returns an expression that evaluates to `result`, if the equation is solvable.
+    fn witness(&self, operators: &[Operators]) -> Option<Solution> {
+        let applied = self.search(self.result, self.operands.len(), operators)?;
+
+        let mut expressions = vec![Expression::Value(self.operands[0])];
+        for (operator, operand) in applied.into_iter().zip(&self.operands[1..]) {
+            expressions.push(Expression::Operator(operator.clone()));
+            expressions.push(Expression::Value(*operand));
         }
-        false
+
+        Some(Solution {
+            operators: expressions,
+        })
     }
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let operators = vec![Operators::Add, Operators::Multiply];
-    let equations: Vec<Equation> = input
+fn calibration_result(input: &str, operators: &[Operators]) -> usize {
+    input
         .lines()
-        .map(|x| Equation::from_line(x, operators.clone()))
-        .collect();
-    Some(
-        equations
-            .iter()
-            .filter(|x| x.is_solvable())
-            .map(|x| x.result)
-            .sum::<usize>(),
-    )
+        .map(Equation::from_line)
+        .filter(|equation| {
+            let Some(solution) = equation.witness(operators) else {
+                return false;
+            };
+            debug_assert_eq!(solution.evaluate(), equation.result);
+            viz!("{} = {solution}", equation.result);
+            true
+        })
+        .map(|equation| equation.result)
+        .sum::<usize>()
+}
+
+pub fn part_one(input: &str) -> Option<usize> {
+    Some(calibration_result(
+        input,
+        &[Operators::Add, Operators::Multiply],
+    ))
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
-    let operators = vec![Operators::Add, Operators::Multiply, Operators::Concatenate];
-    let equations: Vec<Equation> = input
-        .lines()
-        .map(|x| Equation::from_line(x, operators.clone()))
-        .collect();
-    Some(
-        equations
-            .iter()
-            .filter(|x| x.is_solvable())
-            .map(|x| x.result)
-            .sum::<usize>(),
-    )
+    Some(calibration_result(
+        input,
+        &[Operators::Add, Operators::Multiply, Operators::Concatenate],
+    ))
 }
 
 #[cfg(test)]
@@ -175,4 +232,43 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(11387));
     }
+
+    #[test]
+    fn test_witness() {
+        let operators = [Operators::Add, Operators::Multiply, Operators::Concatenate];
+
+        let equation = Equation::from_line("7290: 6 8 6 15");
+        let solution = equation.witness(&operators).unwrap();
+        assert_eq!(solution.to_string(), "6 * 8 || 6 * 15");
+        assert_eq!(solution.evaluate(), 7290);
+
+        assert!(Equation::from_line("83: 17 5")
+            .witness(&operators)
+            .is_none());
+    }
+
+    #[test]
+    fn test_zero_operands() {
+        let operators = [Operators::Add, Operators::Multiply, Operators::Concatenate];
+
+        assert_eq!(part_one("0: 5 0\n"), Some(0));
+        let solution = Equation::from_line("0: 3 4 5 0")
+            .witness(&operators)
+            .unwrap();
+        assert_eq!(solution.evaluate(), 0);
+
+        assert_eq!(Operators::Concatenate.apply(12, 0), 120);
+        assert_eq!(Operators::Concatenate.undo(120, 0), Some(12));
+        assert_eq!(Operators::Concatenate.undo(12, 0), None);
+        assert_eq!(part_one("120: 12 0\n"), Some(0));
+        assert_eq!(part_two("120: 12 0\n"), Some(120));
+    }
+
+    #[test]
+    fn test_missing_operands() {
+        assert!(Equation::from_line("5:")
+            .witness(&[Operators::Add])
+            .is_none());
+        assert_eq!(part_one("5:\n"), Some(0));
+    }
 }
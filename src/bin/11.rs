@@ -1,118 +1,67 @@
-use std::collections::HashMap;
+use advent_of_code::memo::{count, evolve_n, Counts};
 
-advent_of_code::solution!(11);
-
-#[derive(Clone)]
-struct Stone {
-    value: usize,
-    splits: usize,
-}
-
-struct Line {
-    stones: Vec<Stone>,
-    cache: Vec<HashMap<usize, Stone>>,
-}
+advent_of_code::solution!(11, parse: parse_stones);
 
 enum Rule {
     Empty,
-    Even,
+    Even(u32),
     Default,
 }
 
-impl Stone {
-    fn new(value: usize, splits: usize) -> Self {
-        Self { value, splits }
-    }
-
-    fn rule(&self) -> Rule {
-        match self.value {
+impl Rule {
+    fn for_stone(value: usize) -> Self {
+        match value {
             0 => Rule::Empty,
-            _ if self.value.to_string().len() % 2 == 0 => Rule::Even,
-            _ => Rule::Default,
+            _ => {
+                let digits = value.ilog10() + 1;
+                if digits.is_multiple_of(2) {
+                    Rule::Even(digits)
+                } else {
+                    Rule::Default
+                }
+            }
         }
     }
-}
 
-impl Line {
-    fn from_input(input: &str, total_rounds: usize) -> Self {
-        let stones = input
-            .split_whitespace()
-            .map(|s| Stone::new(s.parse().unwrap(), 1))
-            .collect();
-        // The cache should be a Vec of size total_rounds
-        // And each element should be a HashMap of the value to the stone
-        let cache = vec![HashMap::new(); total_rounds + 1];
-        Self { stones, cache }
-    }
-
-    fn apply_stone(&mut self, stone: &Stone, rounds_remaining: usize) -> usize {
-        // First check to see if the cache already has the stone
-        if let Some(stone) = self.cache[rounds_remaining].get(&stone.value) {
-            return stone.splits;
-        }
-
-        if rounds_remaining == 0 {
-            self.cache[rounds_remaining].insert(stone.value, Stone::new(stone.value, 1));
-            return 1;
-        }
-
-        let (first, second) = stone.rule().apply(stone);
-        let first = self.apply_stone(&first, rounds_remaining - 1);
-        if second.is_some() {
-            let second = self.apply_stone(&second.unwrap(), rounds_remaining - 1);
-            self.cache[rounds_remaining]
-                .insert(stone.value, Stone::new(stone.value, first + second));
-            first + second
-        } else {
-            self.cache[rounds_remaining].insert(stone.value, Stone::new(stone.value, first));
-            first
+    /// The stones that replace `value` after a single blink.
+    fn apply(&self, value: usize) -> [Option<usize>; 2] {
+        match self {
+            Rule::Empty => [Some(1), None],
+            Rule::Even(digits) => {
+                // the first half of the digits is the first stone, the second half is the second stone
+                let half = 10_usize.pow(digits / 2);
+                [Some(value / half), Some(value % half)]
+            }
+            Rule::Default => [Some(value * 2024), None],
         }
     }
+}
 
-    fn blink(&mut self, rounds_remaining: usize) -> usize {
-        let mut splits = 0;
-        for stone in &self.stones.clone() {
-            splits += self.apply_stone(stone, rounds_remaining);
-        }
-        splits
-    }
+fn parse_stones(input: &str) -> Counts<usize> {
+    count(input.split_whitespace().map(|s| s.parse().unwrap()))
 }
 
-impl Rule {
-    fn apply(&self, stone: &Stone) -> (Stone, Option<Stone>) {
-        match self {
-            Rule::Empty => (Stone::new(1, 1), None),
-            Rule::Even => {
-                // This needs to split the digits of the number
-                // the first half is the first stone, the second half is the second stone
-                let value = stone.value.to_string();
-                let half = value.len() / 2;
-                let first = value[..half].parse().unwrap();
-                let second = value[half..].parse().unwrap();
-                (Stone::new(first, 1), Some(Stone::new(second, 1)))
-            }
-            Rule::Default => (Stone::new(stone.value * 2024, 1), None),
-        }
-    }
+/// Number of stones after blinking `rounds` times.
+/// Stones never affect each other, so only the count per engraved number is tracked.
+fn blink(input: &str, rounds: usize) -> usize {
+    let stones = evolve_n(parse_stones(input), rounds, |&value| {
+        Rule::for_stone(value).apply(value).into_iter().flatten()
+    });
+    stones.values().sum()
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let num_rounds = 25;
-    let mut line = Line::from_input(input, num_rounds);
-
-    Some(line.blink(num_rounds))
+    Some(blink(input, 25))
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
-    let num_rounds = 75;
-    let mut line = Line::from_input(input, num_rounds);
-
-    Some(line.blink(num_rounds))
+    Some(blink(input, 75))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::memo::Memo;
 
     #[test]
     fn test_part_one() {
@@ -125,4 +74,38 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(65601038650482));
     }
+
+    #[test]
+    fn test_blink() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        assert_eq!(blink(&input, 0), 2);
+        assert_eq!(blink(&input, 6), 22);
+    }
+
+    #[test]
+    fn test_memoised_blink() {
+        // counting recursively per stone must agree with evolving the whole line.
+        let stones_after = |stones: &mut dyn FnMut((usize, usize)) -> usize,
+                            &(value, rounds): &(usize, usize)| {
+            if rounds == 0 {
+                return 1;
+            }
+            Rule::for_stone(value)
+                .apply(value)
+                .into_iter()
+                .flatten()
+                .map(|next| stones((next, rounds - 1)))
+                .sum()
+        };
+
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let mut memo = Memo::new();
+        let total: usize = parse_stones(&input)
+            .iter()
+            .map(|(&value, &n)| n * memo.get_or_compute((value, 75), &stones_after))
+            .sum();
+
+        assert_eq!(total, blink(&input, 75));
+        assert!(memo.hits() > 0);
+    }
 }
@@ -1,6 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-advent_of_code::solution!(13);
+use advent_of_code::linear::{self, min_cost_non_negative, Solution};
+use advent_of_code::viz;
+
+advent_of_code::solution!(13, variants {
+    1: "search" => part_one_search,
+});
 
 #[derive(Debug, Clone)]
 struct Button {
@@ -16,51 +21,24 @@ struct Prize {
 }
 
 impl Prize {
-    fn get_y_for_slope(&self, x: usize) -> usize {
-        let slope = self.y as f64 / self.x as f64;
-        (slope * x as f64).round() as usize
-    }
-
+    /// Manhattan distance from `(x, y)` to the prize, `usize::MAX` once the prize has been passed.
     fn get_distance(&self, x: usize, y: usize) -> usize {
         if x > self.x || y > self.y {
             return usize::MAX;
         }
-        // Manhattan distance
-        // But we should also weight it towards the centerline between the prize and 0,0
-        let x_remaining = self.x - x;
-        let y_remaining = self.y - y;
-        // let distance_from_slope = if self.get_y_for_slope(x) > y {
-        //     self.get_y_for_slope(x) - y
-        // } else {
-        //     y - self.get_y_for_slope(x)
-        // };
-
-        // x_remaining + y_remaining + distance_from_slope
-        x_remaining + y_remaining
+        (self.x - x) + (self.y - y)
     }
 
+    /// Number of presses of `button` that lead from `visit` straight to the prize, if there is one.
     fn is_runnable(&self, visit: &Visited, button: &Button) -> Option<usize> {
-        if visit.x > self.x || visit.y > self.y {
+        if self.overshoot(visit) {
             return None;
         }
-        // This should determine if it is possible to just keep pushing the button until you reach the prize
         let x_remaining = self.x - visit.x;
         let y_remaining = self.y - visit.y;
-        // Check to see if they are divisible by the button dx and dy with no remainder
-        let x_divisible = x_remaining % button.dx == 0;
-        let y_divisible = y_remaining % button.dy == 0;
-        if x_divisible && y_divisible {
-            // Then they have to be divisible by the same number
-            let x_presses = x_remaining / button.dx;
-            let y_presses = y_remaining / button.dy;
-
-            if x_presses == y_presses {
-                return Some(x_presses);
-            } else {
-                return None;
-            }
-        };
-        None
+        let presses = x_remaining.checked_div(button.dx)?;
+        (presses * button.dx == x_remaining && presses * button.dy == y_remaining)
+            .then_some(presses)
     }
 
     fn overshoot(&self, visit: &Visited) -> bool {
@@ -72,6 +50,7 @@ impl Prize {
     }
 }
 
+/// A position of the claw, with the cost to get there.
 #[derive(Debug, Clone)]
 struct Visited {
     x: usize,
@@ -100,122 +79,52 @@ impl Visited {
     }
 
     fn get_next_visited(&self, button: &Button, prize: &Prize) -> Visited {
-        let x = self.x + button.dx;
-        let y = self.y + button.dy;
-        let cost = self.cost + button.cost;
-        let distance = prize.get_distance(x, y);
-        Visited {
-            x,
-            y,
-            cost,
-            distance,
-        }
+        Visited::new(
+            self.x + button.dx,
+            self.y + button.dy,
+            self.cost + button.cost,
+            prize,
+        )
     }
 }
 
-impl PartialEq for Visited {
-    fn eq(&self, other: &Self) -> bool {
-        self.cost == other.cost
-    }
-}
-impl Eq for Visited {}
-impl PartialOrd for Visited {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cost.cmp(&other.cost))
-    }
-}
-impl Ord for Visited {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.cost.cmp(&other.cost)
-    }
-}
-
-struct MinHeap<'a> {
-    target: &'a Prize,
-    size: usize,
-    // distances: Vec<usize>,
-    min_distance: Option<usize>,
-    heap: HashMap<usize, Vec<Visited>>,
+/// Positions still to visit, grouped by their distance to the prize. The closest come out first.
+struct MinHeap {
+    heap: BTreeMap<usize, Vec<Visited>>,
 }
 
-impl<'a> MinHeap<'a> {
-    fn new(target: &'a Prize) -> Self {
+impl MinHeap {
+    fn new() -> Self {
         Self {
-            target,
-            // distances: Vec::new(),
-            min_distance: None,
-            heap: HashMap::new(),
-            size: 0,
+            heap: BTreeMap::new(),
         }
     }
 
+    /// Adds `visited` unless it is past the prize or its position is already waiting.
     fn insert(&mut self, visited: Visited) {
-        let distance = visited.distance;
-        if distance == usize::MAX {
+        if visited.distance == usize::MAX {
             return;
         }
-        if let Some(visited_list) = self.heap.get_mut(&distance) {
-            // See if there is a visited at the same x and y
-            if let Some(visited_index) = visited_list.iter().position(|v| v.is_coincident(&visited))
-            {
-                // let previous = visited_list.get_mut(visited_index).unwrap();
-                // if previous.cost > visited.cost {
-                //     println!("Found previous: {:?}, current: {:?}", previous, visited);
-                //     previous.cost = visited.cost;
-                // } else {
-                return;
-                // }
-            } else {
-                visited_list.push(visited);
-                self.size += 1;
-            }
-            // visited_list.sort();
-
-            // println!(
-            //     "Found {} item list at distance: {}: {:?}",
-            //     visited_list.len(),
-            //     distance,
-            //     visited_list
-            // );
-            // visited_list.sort_by(|a, b| b.cost.cmp(&a.cost));
-        } else {
-            self.heap.insert(distance, vec![visited]);
-            // self.distances.push(distance);
-            // self.distances.sort_by(|a, b| b.cmp(a));
-            // self.distances.sort();
-            self.size += 1;
+        let visited_list = self.heap.entry(visited.distance).or_default();
+        if !visited_list.iter().any(|v| v.is_coincident(&visited)) {
+            visited_list.push(visited);
         }
     }
 
-    fn get_min_distance(&self) -> Option<usize> {
-        if self.min_distance.is_some() {
-            return self.min_distance;
-        };
-        self.heap.keys().min().copied()
-    }
-
     fn get_next(&mut self) -> Option<Visited> {
-        // let distance = self.distances.last()?;
-        // let distance = self.get_min_distance()?;
-        let distance = self.get_min_distance()?;
-        let visited_list = self.heap.get_mut(&distance)?;
-        let visited = visited_list.pop()?;
-
-        if visited_list.is_empty() {
-            self.heap.remove(&distance);
-            // self.distances.pop();
-            self.min_distance = None;
-            self.min_distance = self.get_min_distance();
+        let mut closest = self.heap.first_entry()?;
+        let visited = closest.get_mut().pop();
+        if closest.get().is_empty() {
+            closest.remove();
         }
-        // println!("Visited: {:?}, Target: {:?}", visited, self.target);
-        self.size -= 1;
-        Some(visited)
+        visited
     }
 }
 
 struct ClawMachine {
     button_a: Button,
     button_b: Button,
+    /// Cheapest known cost per position, keyed by [`Visited::get_hash`].
     seen: HashMap<u64, Visited>,
     min_cost: Option<usize>,
 }
@@ -234,117 +143,80 @@ impl ClawMachine {
         self.seen.contains_key(&visit.get_hash())
     }
 
-    fn get_min_cost(&self) -> Option<usize> {
-        self.min_cost
-    }
-
     fn should_visit(&self, visit: &Visited) -> bool {
-        if visit.distance == usize::MAX {
-            return false;
-        }
-        if let Some(visited) = self.seen.get(&visit.get_hash()) {
-            visited.cost > visit.cost
-        } else {
-            true
-        }
+        visit.distance != usize::MAX
+            && self
+                .seen
+                .get(&visit.get_hash())
+                .is_none_or(|visited| visited.cost > visit.cost)
     }
 
+    /// Records the cost of a position and returns the cheapest cost known for it.
     fn set_min_cost(&mut self, visit: &Visited) -> usize {
-        let hash = visit.get_hash();
-        if let Some(visited) = self.seen.get_mut(&hash) {
-            if visited.cost > visit.cost {
-                visited.cost = visit.cost;
-            }
-            visited.cost
-        } else {
-            self.seen.insert(hash, visit.clone());
-            visit.cost
-        }
+        let visited = self
+            .seen
+            .entry(visit.get_hash())
+            .or_insert_with(|| visit.clone());
+        visited.cost = visited.cost.min(visit.cost);
+        visited.cost
     }
 
+    /// Cost of pressing a single button from `visit` until the prize is reached, if that is possible.
     fn try_runnable(&mut self, visit: &Visited, prize: &Prize) -> Option<usize> {
-        if let Some(presses) = prize.is_runnable(visit, &self.button_a) {
-            let button_a = self.button_a.clone();
-            self.run_button(prize, visit.clone(), &button_a);
-            return Some(presses * self.button_a.cost);
-        }
-        if let Some(presses) = prize.is_runnable(visit, &self.button_b) {
-            let button_b = self.button_b.clone();
-            self.run_button(prize, visit.clone(), &button_b);
-            return Some(presses * self.button_b.cost);
+        for button in [self.button_a.clone(), self.button_b.clone()] {
+            if let Some(presses) = prize.is_runnable(visit, &button) {
+                self.run_button(prize, visit.clone(), &button);
+                return Some(presses * button.cost);
+            }
         }
         None
     }
 
-    fn run_button(&mut self, prize: &Prize, visit: Visited, button: &Button) {
-        let mut visit = visit;
+    fn run_button(&mut self, prize: &Prize, mut visit: Visited, button: &Button) {
         while !prize.overshoot(&visit) {
             visit = visit.get_next_visited(button, prize);
             self.set_min_cost(&visit);
         }
     }
 
+    /// Best-first search towards the prize, always expanding the positions closest to it.
     fn find_min_cost_to_prize(&mut self, prize: &Prize) -> Option<usize> {
         self.seen.clear();
-        let mut min_heap = MinHeap::new(prize);
-        let start = Visited::new(0, 0, 0, prize);
-        min_heap.insert(start);
+        let mut min_heap = MinHeap::new();
+        min_heap.insert(Visited::new(0, 0, 0, prize));
         while let Some(visited) = min_heap.get_next() {
-            // println!(
-            //     "({},{}): {} - {} Left in heap. Min Cost: {}",
-            //     visited.x,
-            //     visited.y,
-            //     visited.cost,
-            //     min_heap.size(),
-            //     self.min_cost.unwrap_or(usize::MAX)
-            // );
-
-            if self.has_seen(&visited) {
-                continue;
-            }
-
-            // If we are already over the min cost, then skip
-            if self.min_cost.is_some() && visited.cost >= self.min_cost.unwrap() {
-                continue;
-            }
-
-            // If we have overshot the prize, then skip
-            if prize.overshoot(&visited) {
+            if self.has_seen(&visited)
+                || self
+                    .min_cost
+                    .is_some_and(|min_cost| visited.cost >= min_cost)
+                || prize.overshoot(&visited)
+            {
                 continue;
             }
 
             self.set_min_cost(&visited);
-            // Check if we have reached the prize
             if prize.caught(&visited) {
                 self.min_cost = Some(self.set_min_cost(&visited));
-                // println!("Found prize: {}", self.min_cost.unwrap());
                 continue;
             }
 
             // Try to see if we can just run the button until we reach the prize
             if let Some(runnable_cost) = self.try_runnable(&visited, prize) {
                 let next_cost = runnable_cost + visited.cost;
-                if self.min_cost.is_none() || next_cost < self.min_cost.unwrap() {
+                if self.min_cost.is_none_or(|min_cost| next_cost < min_cost) {
                     self.min_cost = Some(next_cost);
-                    // println!("Found runnable: {}", self.min_cost.unwrap());
                     continue;
                 }
             }
 
-            // Then enqueue the next possible moves
-            let next_visited_a = visited.get_next_visited(&self.button_a, prize);
-            let next_visited_b = visited.get_next_visited(&self.button_b, prize);
-
-            if self.should_visit(&next_visited_a) {
-                min_heap.insert(next_visited_a);
-            }
-            if self.should_visit(&next_visited_b) {
-                min_heap.insert(next_visited_b);
+            for button in [&self.button_a, &self.button_b] {
+                let next = visited.get_next_visited(button, prize);
+                if self.should_visit(&next) {
+                    min_heap.insert(next);
+                }
             }
         }
 
-        // println!("Cached {} positions", self.seen.keys().len());
-
         self.min_cost
     }
 }
@@ -374,143 +246,92 @@ fn parse_prize_line(input: &str) -> Prize {
     }
 }
 
-fn parse_input(input: &str, offset: usize) -> Vec<ClawMachine> {
-    /*
-    Button A: X+94, Y+34
-    Button B: X+22, Y+67
-    Prize: X=8400, Y=5400
-     */
-
-    let mut claw_machines = Vec::new();
-    let mut lines = input.lines();
-    let mut iterations = 0;
-    println!("Parsing input");
-    while let Some(button_line) = lines.next() {
-        let start_time = std::time::Instant::now();
-        let button_a = parse_button_line(button_line);
-        let button_b = parse_button_line(lines.next().unwrap());
-        let mut prize = parse_prize_line(lines.next().unwrap());
-        prize.x += offset;
-        prize.y += offset;
-        let mut claw_machine = ClawMachine::new(button_a, button_b);
-        iterations += 1;
-        // println!("Starting iteration: {}", iterations);
-        claw_machine.find_min_cost_to_prize(&prize);
-        // if let Some(min_cost) = claw_machine.get_min_cost() {
-        //     println!("[{}] Min cost: {}", iterations, min_cost);
-        // } else {
-        //     println!("[{}] No min cost found", iterations);
-        // }
-        println!("[{}] Time: {:?}", iterations, start_time.elapsed());
-        claw_machines.push(claw_machine);
-        lines.next();
-    }
-    claw_machines
+/// Buttons and prize of every machine, with `offset` added to the prize coordinates.
+fn parse_machines(input: &str, offset: usize) -> Vec<(Button, Button, Prize)> {
+    let lines: Vec<&str> = input.lines().filter(|line| !line.is_empty()).collect();
+    lines
+        .chunks(3)
+        .map(|machine| {
+            let mut prize = parse_prize_line(machine[2]);
+            prize.x += offset;
+            prize.y += offset;
+            (
+                parse_button_line(machine[0]),
+                parse_button_line(machine[1]),
+                prize,
+            )
+        })
+        .collect()
 }
 
-fn parse_input_math(input: &str, offset: usize) -> usize {
-    /*
-    Button A: X+94, Y+34
-    Button B: X+22, Y+67
-    Prize: X=8400, Y=5400
-     */
-
-    let mut lines = input.lines();
-    let mut cost = 0;
-    let mut iterations = 0;
-    while let Some(button_line) = lines.next() {
-        let start_time = std::time::Instant::now();
-        let button_a = parse_button_line(button_line);
-        let button_b = parse_button_line(lines.next().unwrap());
-        let mut prize = parse_prize_line(lines.next().unwrap());
-        prize.x += offset;
-        prize.y += offset;
-        iterations += 1;
-        // Instead of that, we're going to do math
-        // There should be some amount of button pushes where the prize is reachable
-        // A_Pushes * A_DX + B_Pushes * B_DX = Prize_X
-        // A_Pushes * A_DY + B_Pushes * B_DY = Prize_Y
-        // A_Pushes * A_Cost + B_Pushes * B_Cost = Min_Cost
-        // A_Pushes = (Prize_X - B_Pushes * B_DX) / A_DX
-        // B_Pushes = (Prize_Y - A_Pushes * A_DY) / B_DY
-        let x1 = button_a.dx;
-        let y1 = button_a.dy;
-        let x2 = button_b.dx;
-        let y2 = button_b.dy;
-        let z1 = prize.x;
-        let z2 = prize.y;
-        let b = (z2 * x1 - z1 * x2) / (y2 * x1 - y1 * x2);
-        let a = (z1 - b * y1) / x1;
-        // let check_x = (a * x1) + (b * y1);
-        // let check_y = (a * x2) + (b * y2);
-        if (x1 * a + y1 * b, x2 * a + y2 * b) == (z1, z2) {
-            let next_cost = (a * button_a.cost) + (b * button_b.cost);
-
-            cost += next_cost;
+/// Fewest tokens needed to win the prize, or `None` if it cannot be reached.
+fn min_tokens(a: &Button, b: &Button, prize: &Prize) -> Option<i128> {
+    let [ax, ay, bx, by, px, py] = [a.dx, a.dy, b.dx, b.dy, prize.x, prize.y].map(|v| v as i128);
+    let (cost_a, cost_b) = (a.cost as i128, b.cost as i128);
+
+    let presses = match linear::solve([[ax, bx], [ay, by]], [px, py]) {
+        solution @ Solution::Unique(_) => solution
+            .integers()
+            .filter(|presses| presses.iter().all(|&n| n >= 0))?,
+        // both buttons move along the line through the prize, so reaching its x also reaches its y.
+        // If neither button moves along x, the y equation decides instead.
+        Solution::Infinite if ax > 0 || bx > 0 => cheapest_presses(ax, bx, px, cost_a, cost_b)?,
+        Solution::Infinite => cheapest_presses(ay, by, py, cost_a, cost_b)?,
+        Solution::None => return None,
+    };
+
+    Some(presses[0] * cost_a + presses[1] * cost_b)
+}
+
+/// Cheapest non-negative presses with `a * presses_a + b * presses_b = target`.
+/// A button that does not move along the axis is never pressed.
+fn cheapest_presses(
+    a: i128,
+    b: i128,
+    target: i128,
+    cost_a: i128,
+    cost_b: i128,
+) -> Option<[i128; 2]> {
+    match (a, b) {
+        (0, 0) => (target == 0).then_some([0, 0]),
+        (0, b) => (target % b == 0).then_some([0, target / b]),
+        (a, 0) => (target % a == 0).then_some([target / a, 0]),
+        (a, b) => {
+            let (presses_a, presses_b) = min_cost_non_negative(a, b, target, cost_a, cost_b)?;
+            Some([presses_a, presses_b])
         }
-        // if check_x == z1 && check_y == z2 {
-        // println!("Found a: {}, b: {}", a, b);
-        // println!("Cost: {}", cost);
-        // } else {
-        // println!("Failed to find a: {}, b: {}", a, b);
-        // }
-        // println!("[{}] Time: {:?}", iterations, start_time.elapsed());
-        lines.next();
     }
-    cost as usize
 }
 
-// pub fn part_one(input: &str) -> Option<usize> {
-//     let claw_machines = parse_input(input, 0);
-//     let total_cost = claw_machines
-//         .iter()
-//         .map(|claw_machine| claw_machine.get_min_cost().unwrap_or(0))
-//         .sum();
-//     Some(total_cost)
-// }
-
-// pub fn part_two(input: &str) -> Option<usize> {
-//     let claw_machines = parse_input(input, 10000000000000);
-//     let total_cost = claw_machines
-//         .iter()
-//         .map(|claw_machine| claw_machine.get_min_cost().unwrap_or(0))
-//         .sum();
-//     Some(total_cost)
-//     // None
-// }
-
-use itertools::Itertools;
-
-fn solve(x1: i64, x2: i64, y1: i64, y2: i64, z1: i64, z2: i64) -> i64 {
-    let b = (z2 * x1 - z1 * x2) / (y2 * x1 - y1 * x2);
-    let a = (z1 - b * y1) / x1;
-    if (x1 * a + y1 * b, x2 * a + y2 * b) != (z1, z2) {
-        return 0;
-    }
-    a * 3 + b
+fn total_tokens(input: &str, offset: usize) -> Option<usize> {
+    let total: i128 = parse_machines(input, offset)
+        .iter()
+        .filter_map(|(a, b, prize)| min_tokens(a, b, prize))
+        .sum();
+    usize::try_from(total).ok()
 }
 
-fn stolen(input: &str, offset: i64) -> i64 {
-    let xs = input
-        .split(|c: char| !c.is_ascii_digit())
-        .filter(|w| !w.is_empty())
-        .map(|w| w.parse().unwrap())
-        .tuples();
-    let mut p = 0;
-    for (x1, x2, y1, y2, z1, z2) in xs {
-        p += solve(x1, x2, y1, y2, z1 + offset, z2 + offset);
-    }
-    p
+/// Walks towards the prize with a best-first search over button presses.
+/// Too slow for the offset prizes of part two.
+fn part_one_search(input: &str) -> Option<usize> {
+    let total_cost = parse_machines(input, 0)
+        .into_iter()
+        .enumerate()
+        .map(|(index, (button_a, button_b, prize))| {
+            let min_cost = ClawMachine::new(button_a, button_b).find_min_cost_to_prize(&prize);
+            viz!("[{index}] Min cost: {min_cost:?}");
+            min_cost.unwrap_or(0)
+        })
+        .sum();
+    Some(total_cost)
 }
 
-// This is the math version
 pub fn part_one(input: &str) -> Option<usize> {
-    let cost = stolen(input, 0);
-    Some(cost as usize)
+    total_tokens(input, 0)
 }
+
 pub fn part_two(input: &str) -> Option<usize> {
-    let cost = stolen(input, 10000000000000);
-    Some(cost as usize)
+    total_tokens(input, 10000000000000)
 }
 
 #[cfg(test)]
@@ -528,4 +349,33 @@ mod tests_13 {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(875318608908));
     }
+
+    #[test]
+    fn test_part_one_search() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        assert_eq!(part_one_search(&input), part_one(&input));
+    }
+
+    #[test]
+    fn test_collinear_buttons() {
+        // B moves half as far as A for a third of the price.
+        let input = "Button A: X+2, Y+2\nButton B: X+1, Y+1\nPrize: X=5, Y=5\n\n\
+                     Button A: X+4, Y+6\nButton B: X+2, Y+3\nPrize: X=5, Y=7\n";
+        assert_eq!(part_one(input), Some(5));
+    }
+
+    #[test]
+    fn test_buttons_without_x_movement() {
+        // both buttons only move vertically, B twice as far as A for a third of the price.
+        let vertical = "Button A: X+0, Y+1\nButton B: X+0, Y+2\nPrize: X=0, Y=6\n";
+        assert_eq!(part_one(vertical), Some(3));
+        assert_eq!(
+            part_one("Button A: X+0, Y+1\nButton B: X+0, Y+2\nPrize: X=1, Y=6\n"),
+            Some(0)
+        );
+
+        // A does not move at all, so only B is pressed.
+        let stuck = "Button A: X+0, Y+0\nButton B: X+2, Y+3\nPrize: X=4, Y=6\n";
+        assert_eq!(part_one(stuck), Some(2));
+    }
 }
@@ -1,294 +1,128 @@
-use core::panic;
+use advent_of_code::grid::{Direction, Grid};
+use advent_of_code::push::{ObjectId, PushWorld, Replay};
+use advent_of_code::render::GifRenderer;
+use advent_of_code::viz::{self, Visualize};
 
 advent_of_code::solution!(15);
 
-#[derive(Clone, Copy)]
-enum MoveDir {
-    Up,
-    Down,
-    Left,
-    Right,
+#[derive(Clone, Copy, PartialEq)]
+enum Item {
+    Robot,
+    Box,
+    /// A box spanning two cells, drawn as `[]`.
+    WideBox,
 }
 
-impl MoveDir {
-    fn get_next_move(&self, x: usize, y: usize) -> (usize, usize) {
-        match self {
-            MoveDir::Up => (x, y - 1),
-            MoveDir::Down => (x, y + 1),
-            MoveDir::Left => (x - 1, y),
-            MoveDir::Right => (x + 1, y),
-        }
-    }
-    fn from_char(c: char) -> MoveDir {
-        match c {
-            '^' => MoveDir::Up,
-            'v' => MoveDir::Down,
-            '<' => MoveDir::Left,
-            '>' => MoveDir::Right,
-            _ => panic!("Invalid move: {}", c),
-        }
-    }
-}
-
-#[derive(Clone)]
-enum Cell {
-    Empty,
-    Wall,
-    WarehouseBox(WarehouseBox),
-    DoubleSizeBoxLeft(WarehouseBox),
-    DoubleSizeBoxRight(WarehouseBox),
-    Robot(Robot),
-}
-
-impl Cell {
-    fn from_char(x: usize, y: usize, c: char) -> Cell {
-        match c {
-            '.' => Cell::Empty,
-            '#' => Cell::Wall,
-            'O' => Cell::WarehouseBox(WarehouseBox { x, y }),
-            '[' => Cell::DoubleSizeBoxLeft(WarehouseBox { x, y }),
-            ']' => Cell::DoubleSizeBoxRight(WarehouseBox { x, y }),
-            '@' => Cell::Robot(Robot {
-                x,
-                y,
-                move_index: 0,
-                remaining_moves: Vec::new(),
-            }),
-            _ => panic!("Invalid cell: {}", c),
-        }
-    }
-
-    fn get_gps(&self) -> usize {
-        match self {
-            Cell::WarehouseBox(b) => b.x + 100 * b.y,
-            Cell::DoubleSizeBoxLeft(b) => b.x + 100 * b.y,
-            _ => 0,
-        }
-    }
-
-    fn move_to(&mut self, x: usize, y: usize) {
-        match self {
-            Cell::WarehouseBox(b) => b.move_to(x, y),
-            Cell::DoubleSizeBoxLeft(b) => b.move_to(x, y),
-            Cell::DoubleSizeBoxRight(b) => b.move_to(x, y),
-            Cell::Robot(r) => r.move_to(x, y),
-            _ => (),
-        }
-    }
-
-    fn get_name(&self) -> &str {
-        match self {
-            Cell::Empty => "Empty",
-            Cell::Wall => "Wall",
-            Cell::WarehouseBox(_) => "WarehouseBox",
-            Cell::DoubleSizeBoxLeft(_) => "DoubleSizeBoxLeft",
-            Cell::DoubleSizeBoxRight(_) => "DoubleSizeBoxRight",
-            Cell::Robot(_) => "Robot",
-        }
-    }
-}
-
-#[derive(Clone)]
-struct Robot {
-    x: usize,
-    y: usize,
-    move_index: usize,
-    remaining_moves: Vec<MoveDir>,
-}
-
-impl Robot {
-    fn get_next_move(&mut self) -> Option<&MoveDir> {
-        let next_move = self.remaining_moves.get(self.move_index);
-        self.move_index += 1;
-        next_move
-    }
-
-    fn move_to(&mut self, x: usize, y: usize) {
-        self.x = x;
-        self.y = y;
-    }
-}
-
-#[derive(Clone)]
-struct WarehouseBox {
-    x: usize,
-    y: usize,
-}
-
-impl WarehouseBox {
-    fn move_to(&mut self, x: usize, y: usize) {
-        self.x = x;
-        self.y = y;
+fn parse_move(c: char) -> Direction {
+    match c {
+        '^' => Direction::Up,
+        'v' => Direction::Down,
+        '<' => Direction::Left,
+        '>' => Direction::Right,
+        _ => panic!("Invalid move: {}", c),
     }
 }
 
 struct Warehouse {
-    cells: Vec<Vec<Cell>>,
-    robot: Robot,
+    replay: Replay<Item>,
+    robot: ObjectId,
 }
 
 impl Warehouse {
     fn from_str(input: &str) -> Warehouse {
-        // First, break by a double newline
-        let mut cells = Vec::new();
-        let mut split = input.split("\n\n");
-        let grid = split.next().unwrap().lines();
-        let directions = split.next().unwrap();
-
-        for (y, line) in grid.enumerate() {
-            let mut row = Vec::new();
-            for (x, c) in line.chars().enumerate() {
-                let cell = Cell::from_char(x, y, c);
-                if let Cell::Robot(robot) = cell {
-                    let mut remaining_moves = Vec::new();
-                    for c in directions.chars() {
-                        // Skip newlines
-                        if c == '\n' {
-                            continue;
-                        }
-                        remaining_moves.push(MoveDir::from_char(c));
-                    }
-                    row.push(Cell::Robot(Robot {
-                        x: robot.x,
-                        y: robot.y,
-                        remaining_moves,
-                        move_index: 0,
-                    }));
-                } else {
-                    row.push(cell);
-                }
+        let (map, moves) = input.split_once("\n\n").unwrap();
+        let cells = Grid::from_chars(map);
+        let mut world = PushWorld::new(cells.map(|_, &c| c == '#'));
+
+        let mut robot = None;
+        for (pos, &c) in cells.iter() {
+            match c {
+                '@' => robot = Some(world.add(Item::Robot, pos, vec![(0, 0)])),
+                'O' => _ = world.add(Item::Box, pos, vec![(0, 0)]),
+                '[' => _ = world.add(Item::WideBox, pos, vec![(0, 0), (1, 0)]),
+                '.' | '#' | ']' => {}
+                _ => panic!("Invalid cell: {}", c),
             }
-            cells.push(row);
         }
 
-        // Find the robot
-        let robot = cells
-            .iter()
-            .flat_map(|row| row.iter())
-            .find_map(|cell| match cell {
-                Cell::Robot(robot) => Some(robot.clone()),
-                _ => None,
-            })
-            .unwrap();
-
-        // Replace the robot cell with an empty cell
-        cells[robot.y][robot.x] = Cell::Empty;
-
-        Warehouse { cells, robot }
+        let robot = robot.expect("the warehouse has no robot");
+        let moves = moves
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(parse_move)
+            .collect();
+        Warehouse {
+            replay: Replay::new(world, robot, moves),
+            robot,
+        }
     }
 
-    fn get_cell(&self, x: usize, y: usize) -> &Cell {
-        &self.cells[y][x]
+    fn world(&self) -> &PushWorld<Item> {
+        &self.replay.world
     }
 
-    fn move_cells(&mut self, x: usize, y: usize, dir: MoveDir, other_half_checked: bool) {
-        let cell = self.get_cell(x, y).clone();
-        if let Cell::Empty = cell {
-            return;
-        }
-        if let Cell::Wall = cell {
-            return;
-        }
-        if !other_half_checked {
-            if let Cell::DoubleSizeBoxLeft(_) = cell {
-                self.move_cells(x + 1, y, dir, true);
-            }
-            if let Cell::DoubleSizeBoxRight(_) = cell {
-                self.move_cells(x - 1, y, dir, true);
-            }
-        }
-        let (next_x, next_y) = dir.get_next_move(x, y);
-        self.cells[y][x] = Cell::Empty;
-        self.move_cells(next_x, next_y, dir, false);
-        self.cells[next_y][next_x] = cell.clone();
-        self.cells[next_y][next_x].move_to(next_x, next_y);
+    fn try_move(&mut self) {
+        self.replay.run();
     }
 
-    fn try_move_cell_in_dir(
-        &mut self,
-        x: usize,
-        y: usize,
-        dir: MoveDir,
-        other_half_checked: bool,
-    ) -> bool {
-        let cell = self.get_cell(x, y).clone();
-        // If it's empty, return true
-        if let Cell::Empty = cell {
-            return true;
-        }
-        if let Cell::Wall = cell {
-            return false;
-        }
-        let (next_x, next_y) = dir.get_next_move(x, y);
-        // If it's a right side of a double box moving left, or a left side of a double box moving right
-        // Then we should set that the other half has been checked
-        let is_pushing_in_dir = match (cell.clone(), dir) {
-            (Cell::DoubleSizeBoxLeft(_), MoveDir::Right) => true,
-            (Cell::DoubleSizeBoxRight(_), MoveDir::Left) => true,
-            _ => false,
+    /// Performs all moves, recording a frame after each of them if a gif was requested.
+    fn try_move_recorded(&mut self) {
+        let (width, height) = (self.world().width(), self.world().height());
+        let Some(mut gif) =
+            GifRenderer::from_args(width, height, |options| options.scale(8).frame_skip(10))
+        else {
+            return self.try_move();
         };
-        let mut next_can_move = self.try_move_cell_in_dir(next_x, next_y, dir, is_pushing_in_dir);
-        if !is_pushing_in_dir && !other_half_checked {
-            if let Cell::DoubleSizeBoxLeft(_) = cell {
-                next_can_move = next_can_move && self.try_move_cell_in_dir(x + 1, y, dir, true);
-            }
-            if let Cell::DoubleSizeBoxRight(_) = cell {
-                next_can_move = next_can_move && self.try_move_cell_in_dir(x - 1, y, dir, true);
-            }
-        }
-        if next_can_move {
-            return true;
+
+        gif.push_frame(|x, y| self.palette_index(x, y));
+        while self.replay.step().is_some() {
+            gif.push(|x, y| self.palette_index(x, y));
         }
-        false
+        gif.push_frame(|x, y| self.palette_index(x, y));
     }
 
-    fn try_move(&mut self) {
-        // Get the next move from the robot
-        // Then check in the direction of the move if it's possible
-        // Boxes can be pushed
-        // If the move is possible, update the robot's position
-        // If the move is not possible, do nothing
-        let (robot_x, robot_y) = (self.robot.x, self.robot.y);
-        let next_move = self.robot.get_next_move();
-        if next_move.is_none() {
-            return;
+    /// Color of a cell in the default gif palette.
+    fn palette_index(&self, x: usize, y: usize) -> u8 {
+        match self.world().object_at((x, y)) {
+            Some(id) if id == self.robot => 4,
+            Some(_) => 6,
+            None if self.world().is_wall((x, y)) => 1,
+            None => 0,
         }
-        let next_move = *next_move.unwrap();
-        let (x, y) = next_move.get_next_move(robot_x, robot_y);
-        if self.try_move_cell_in_dir(x, y, next_move, false) {
-            self.move_cells(x, y, next_move, false);
-            self.robot.move_to(x, y);
-        };
-
-        self.try_move();
     }
 
     fn get_gps(&self) -> usize {
-        self.cells
-            .iter()
-            .flat_map(|row| row.iter())
-            .map(|cell| cell.get_gps())
+        self.world()
+            .objects()
+            .filter(|(_, object)| object.kind != Item::Robot)
+            .map(|(_, object)| object.origin.0 + 100 * object.origin.1)
             .sum()
     }
+}
 
-    fn print(&self) {
-        for (y, row) in self.cells.iter().enumerate() {
-            for (x, cell) in row.iter().enumerate() {
-                if x == self.robot.x && y == self.robot.y {
-                    print!("@");
-                    continue;
-                }
-                match cell {
-                    Cell::Empty => print!("."),
-                    Cell::Wall => print!("#"),
-                    Cell::WarehouseBox(_) => print!("O"),
-                    Cell::Robot(_) => print!("@"),
-                    Cell::DoubleSizeBoxLeft(_) => print!("["),
-                    Cell::DoubleSizeBoxRight(_) => print!("]"),
-                }
+impl Visualize for Warehouse {
+    fn render(&self) -> String {
+        let world = self.world();
+        let mut s = String::new();
+        for y in 0..world.height() {
+            if y > 0 {
+                s.push('\n');
+            }
+            for x in 0..world.width() {
+                let c = match world.object_at((x, y)).map(|id| world.object(id)) {
+                    Some(object) => match object.kind {
+                        Item::Robot => '@',
+                        Item::Box => 'O',
+                        Item::WideBox if object.origin == (x, y) => '[',
+                        Item::WideBox => ']',
+                    },
+                    None if world.is_wall((x, y)) => '#',
+                    None => '.',
+                };
+                s.push(c);
             }
-            println!();
         }
+        s
     }
 }
 
@@ -324,7 +158,7 @@ fn double_size_string(s: &str) -> String {
 pub fn part_one(input: &str) -> Option<usize> {
     let mut warehouse = Warehouse::from_str(input);
     warehouse.try_move();
-    warehouse.print();
+    viz::show("Final warehouse", &warehouse);
     Some(warehouse.get_gps())
 }
 
@@ -335,9 +169,9 @@ pub fn part_two(input: &str) -> Option<usize> {
     let directions = split.next().unwrap();
     let input = format!("{}\n\n{}", doubled, directions);
     let mut warehouse = Warehouse::from_str(&input);
-    // warehouse.print();
-    warehouse.try_move();
-    warehouse.print();
+    viz::show("Initial warehouse", &warehouse);
+    warehouse.try_move_recorded();
+    viz::show("Final warehouse", &warehouse);
     Some(warehouse.get_gps())
 }
 
@@ -375,4 +209,16 @@ mod tests_15 {
         ));
         assert_eq!(result, Some(618));
     }
+
+    #[test]
+    fn test_undo_all_moves() {
+        let input = advent_of_code::template::read_file_part("examples", DAY, 1);
+        let mut warehouse = Warehouse::from_str(&input);
+        let initial = warehouse.render();
+
+        warehouse.try_move();
+        assert_ne!(warehouse.render(), initial);
+        while warehouse.replay.step_back() {}
+        assert_eq!(warehouse.render(), initial);
+    }
 }
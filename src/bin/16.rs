@@ -4,6 +4,8 @@ use std::{
     hash::{Hash, Hasher},
 };
 
+use advent_of_code::viz;
+
 advent_of_code::solution!(16);
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
@@ -221,7 +223,7 @@ impl Cell {
     fn get_minimum_cost_entries_to_exit_facing(&self, exit_facing: &Facing) -> Vec<Facing> {
         let mut entries = Vec::new();
         match self {
-            Cell::Empty(cost) => {
+            Cell::Empty(_) => {
                 let min_cost = self.get_minimum_cost_to_exit_facing(exit_facing);
                 for f in Facing::get_all_facings() {
                     let cost = self.get_cost_from_facing_to_exit(&f, exit_facing);
@@ -230,7 +232,7 @@ impl Cell {
                     }
                 }
             }
-            Cell::End(cost) => {
+            Cell::End(_) => {
                 let min_cost = self.get_minimum_cost();
                 for f in Facing::get_all_facings() {
                     let cost = self.get_cost_from_entry(&f);
@@ -239,7 +241,6 @@ impl Cell {
                     }
                 }
             }
-            Cell::Reindeer(cost) => {}
             _ => {}
         }
         entries
@@ -250,10 +251,7 @@ impl Cell {
     }
 
     fn is_end(&self) -> bool {
-        match self {
-            Cell::End(_) => true,
-            _ => false,
-        }
+        matches!(self, Cell::End(_))
     }
 }
 
@@ -392,8 +390,8 @@ impl Maze {
                 current_cell.get_minimum_cost_entries_to_exit_facing(&facing);
             if current_cell.has_multiple_entries(&facing) {
                 // If there are multiple entries, we need to add the current cell to the queue
-                println!("Multiple entries: ({}, {})", x, y);
-                println!("Current Facings: {:?}", current_facings);
+                viz!("Multiple entries: ({}, {})", x, y);
+                viz!("Current Facings: {:?}", current_facings);
             }
 
             for current_facing in current_facings {
@@ -1,10 +1,13 @@
-use std::fmt::Debug;
+use std::fmt::{self, Debug, Display};
 
+use advent_of_code::viz;
+use advent_of_code::vm::{self, Cpu, Flow, InstructionSet, Status};
 use itertools::Itertools;
 
-advent_of_code::solution!(17);
+advent_of_code::solution!(17, parse: Computer::parse_from_input);
 
 #[derive(Clone)]
+#[allow(clippy::upper_case_acronyms)]
 enum Instruction {
     ADV(Operand),
     BXL(Operand),
@@ -31,6 +34,22 @@ impl Debug for Instruction {
     }
 }
 
+impl Display for Instruction {
+    /// Pseudo-code for the instruction, as used by the disassembler.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::ADV(operand) => write!(f, "a = a >> {operand}"),
+            Instruction::BXL(operand) => write!(f, "b = b ^ {operand}"),
+            Instruction::BST(operand) => write!(f, "b = {operand} % 8"),
+            Instruction::JNZ(operand) => write!(f, "if a != 0 goto {operand}"),
+            Instruction::BXC(_operand) => write!(f, "b = b ^ c"),
+            Instruction::OUT(operand) => write!(f, "out {operand} % 8"),
+            Instruction::BDV(operand) => write!(f, "b = a >> {operand}"),
+            Instruction::CDV(operand) => write!(f, "c = a >> {operand}"),
+        }
+    }
+}
+
 #[derive(Clone)]
 enum Operand {
     Literal(usize),
@@ -46,8 +65,20 @@ impl Debug for Operand {
     }
 }
 
+impl Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Literal(value) => write!(f, "{value}"),
+            Operand::Combo(4) => write!(f, "a"),
+            Operand::Combo(5) => write!(f, "b"),
+            Operand::Combo(6) => write!(f, "c"),
+            Operand::Combo(value) => write!(f, "{value}"),
+        }
+    }
+}
+
 impl Operand {
-    fn get_value(&self, state: &ComputerState) -> usize {
+    fn get_value(&self, state: &Cpu<usize>) -> usize {
         match self {
             Operand::Literal(value) => *value,
             Operand::Combo(register) => match register {
@@ -55,9 +86,9 @@ impl Operand {
                 1 => 1,
                 2 => 2,
                 3 => 3,
-                4 => state.register_a.value,
-                5 => state.register_b.value,
-                6 => state.register_c.value,
+                4 => state.registers[REGISTER_A],
+                5 => state.registers[REGISTER_B],
+                6 => state.registers[REGISTER_C],
                 _ => panic!("Invalid Combo Value"),
             },
         }
@@ -87,119 +118,76 @@ impl Instruction {
         }
     }
 
-    fn perform_division<'a>(&self, state: &'a mut ComputerState) -> &'a mut ComputerState {
-        let numerator = state.register_a.value;
-        match self {
-            Instruction::ADV(operand) => {
-                let divisor = 2_usize.pow(operand.get_value(state) as u32);
-                let quotient = numerator / divisor;
-                state.register_a.value = quotient;
-            }
-            Instruction::BDV(operand) => {
-                let divisor = 2_usize.pow(operand.get_value(state) as u32);
-                let quotient = numerator / divisor;
-                state.register_b.value = quotient;
-            }
-            Instruction::CDV(operand) => {
-                let divisor = 2_usize.pow(operand.get_value(state) as u32);
-                let quotient = numerator / divisor;
-                state.register_c.value = quotient;
-            }
+    fn perform_division(&self, state: &mut Cpu<usize>) {
+        let numerator = state.registers[REGISTER_A];
+        let (operand, target) = match self {
+            Instruction::ADV(operand) => (operand, REGISTER_A),
+            Instruction::BDV(operand) => (operand, REGISTER_B),
+            Instruction::CDV(operand) => (operand, REGISTER_C),
             _ => panic!("Invalid instruction"),
-        }
-
-        state
+        };
+        let divisor = 2_usize.pow(operand.get_value(state) as u32);
+        state.registers[target] = numerator / divisor;
     }
+}
 
-    fn get_operand_value(&self, state: &ComputerState) -> usize {
-        match self {
-            Instruction::ADV(operand) => operand.get_value(state),
-            Instruction::BXL(operand) => operand.get_value(state),
-            Instruction::BST(operand) => operand.get_value(state),
-            Instruction::JNZ(operand) => operand.get_value(state),
-            Instruction::BXC(operand) => operand.get_value(state),
-            Instruction::OUT(operand) => operand.get_value(state),
-            Instruction::BDV(operand) => operand.get_value(state),
-            Instruction::CDV(operand) => operand.get_value(state),
-        }
-    }
+const REGISTER_A: usize = 0;
+const REGISTER_B: usize = 1;
+const REGISTER_C: usize = 2;
 
-    fn process<'a>(&self, mut state: &'a mut ComputerState) -> &'a mut ComputerState {
-        // println!("{:?} - {}", self, self.get_operand_value(state));
-        match self {
+/// The 3-bit computer. Every instruction is followed by its operand, so instructions are two addresses wide.
+#[derive(Clone)]
+struct ThreeBitComputer;
+
+impl InstructionSet for ThreeBitComputer {
+    type Instruction = Instruction;
+    type Word = usize;
+
+    const WIDTH: usize = 2;
+
+    fn execute(&self, instruction: &Instruction, state: &mut Cpu<usize>) -> Flow {
+        match instruction {
             Instruction::ADV(_operand) => {
-                state = self.perform_division(state);
-                state.instruction_pointer += 2;
+                instruction.perform_division(state);
             }
             Instruction::BXL(operand) => {
-                state.register_b.value ^= operand.get_value(state);
-                state.instruction_pointer += 2;
+                state.registers[REGISTER_B] ^= operand.get_value(state);
             }
             Instruction::BST(operand) => {
                 let value = operand.get_value(state) % 8;
-                state.register_b.value = value;
-                state.instruction_pointer += 2;
+                state.registers[REGISTER_B] = value;
             }
             Instruction::JNZ(operand) => {
-                if state.register_a.value != 0 {
-                    state.instruction_pointer = operand.get_value(state);
-                } else {
-                    state.instruction_pointer += 2;
+                if state.registers[REGISTER_A] != 0 {
+                    return Flow::Jump(operand.get_value(state));
                 }
             }
             Instruction::BXC(_operand) => {
-                state.register_b.value ^= state.register_c.value;
-                state.instruction_pointer += 2;
+                state.registers[REGISTER_B] ^= state.registers[REGISTER_C];
             }
             Instruction::OUT(operand) => {
                 state.output.push(operand.get_value(state) % 8);
-                state.instruction_pointer += 2;
             }
             Instruction::BDV(_operand) => {
-                state = self.perform_division(state);
-                state.instruction_pointer += 2;
+                instruction.perform_division(state);
             }
             Instruction::CDV(_operand) => {
-                state = self.perform_division(state);
-                state.instruction_pointer += 2;
+                instruction.perform_division(state);
             }
         }
 
-        state
+        Flow::Next
     }
 }
 
-#[derive(Debug, Clone)]
-struct Register {
-    value: usize,
-}
-
-#[derive(Clone)]
-struct ComputerState {
-    register_a: Register,
-    register_b: Register,
-    register_c: Register,
-    instruction_pointer: usize,
-    output: Vec<usize>,
-}
-
-impl Debug for ComputerState {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("ComputerState")
-            .field("A", &self.register_a.value)
-            .field("B", &self.register_b.value)
-            .field("C", &self.register_c.value)
-            .field("IP", &self.instruction_pointer)
-            .field("output", &self.output)
-            .finish()
-    }
-}
+/// Upper bound on executed instructions, so programs that never halt are rejected instead of hanging.
+const MAX_STEPS: usize = 1_000_000;
 
 #[derive(Clone)]
 struct Computer {
-    state: ComputerState,
+    machine: vm::Machine<ThreeBitComputer>,
+    initial_registers: Vec<usize>,
     program_lines: String,
-    instructions: Vec<Instruction>,
 }
 
 impl Computer {
@@ -208,33 +196,14 @@ impl Computer {
         let register_a_line = lines.next().unwrap();
         let register_b_line = lines.next().unwrap();
         let register_c_line = lines.next().unwrap();
-        let skip_line = lines.next().unwrap();
+        // a blank line separates the registers from the program.
+        lines.next();
         let program_lines = lines.next().unwrap().split_whitespace().last().unwrap();
 
-        let register_a = Register {
-            value: register_a_line
-                .split_whitespace()
-                .last()
-                .unwrap()
-                .parse()
-                .unwrap(),
-        };
-        let register_b = Register {
-            value: register_b_line
-                .split_whitespace()
-                .last()
-                .unwrap()
-                .parse()
-                .unwrap(),
-        };
-        let register_c = Register {
-            value: register_c_line
-                .split_whitespace()
-                .last()
-                .unwrap()
-                .parse()
-                .unwrap(),
-        };
+        let initial_registers: Vec<usize> = [register_a_line, register_b_line, register_c_line]
+            .iter()
+            .map(|line| line.split_whitespace().last().unwrap().parse().unwrap())
+            .collect();
 
         let mut instructions = Vec::new();
 
@@ -245,28 +214,27 @@ impl Computer {
             ));
         }
 
+        let mut machine = vm::Machine::new(ThreeBitComputer, instructions, 3);
+        machine.reset(initial_registers.clone());
+
         Computer {
-            state: ComputerState {
-                register_a,
-                register_b,
-                register_c,
-                instruction_pointer: 0,
-                output: Vec::new(),
-            },
+            machine,
+            initial_registers,
             program_lines: program_lines.to_string(),
-            instructions,
         }
     }
 
-    fn run(&mut self) {
-        while self.state.instruction_pointer < self.instructions.len() {
-            let instruction = &self.instructions[self.state.instruction_pointer];
-            instruction.process(&mut self.state);
-        }
+    /// Runs the program until it halts. Returns `false` if it did not halt within `MAX_STEPS` instructions.
+    fn run(&mut self) -> bool {
+        self.machine.run_with_limit(MAX_STEPS) == Status::Halted
     }
 
-    fn get_instructions(&self) -> String {
-        self.program_lines.clone()
+    /// Runs the program from scratch with a different value in register A.
+    fn run_with_a(&mut self, a: usize) -> bool {
+        let mut registers = self.initial_registers.clone();
+        registers[REGISTER_A] = a;
+        self.machine.reset(registers);
+        self.run()
     }
 
     fn get_instructions_number(&self) -> Vec<usize> {
@@ -277,83 +245,68 @@ impl Computer {
     }
 
     fn get_output_string(&self) -> String {
-        self.state.output.iter().map(|x| x.to_string()).join(",")
+        self.machine
+            .cpu
+            .output
+            .iter()
+            .map(|x| x.to_string())
+            .join(",")
     }
 
-    fn get_output_number(&self) -> Vec<usize> {
-        self.state.output.clone()
+    /// Renders the program as pseudo-code, one instruction per line.
+    fn disassemble(&self) -> String {
+        self.machine
+            .program
+            .iter()
+            .enumerate()
+            .step_by(ThreeBitComputer::WIDTH)
+            .map(|(address, instruction)| format!("{address:02}: {instruction}"))
+            .join("\n")
     }
 
-    fn print_output(&self) {
-        println!("{}", self.get_output_string());
+    /// Finds the lowest value for register A that makes the program output itself.
+    ///
+    /// Assumes the shape every puzzle input has: a single loop that outputs one value per iteration
+    /// based on the lowest bits of A, then shifts A right by three bits. The last output therefore only
+    /// depends on the highest three bits of A, so A is reconstructed three bits at a time from the
+    /// program tail, backtracking whenever no digit reproduces the next output.
+    fn find_quine(&self) -> Option<usize> {
+        let program = self.get_instructions_number();
+        let mut computer = self.clone();
+        computer.search_quine(&program, 0, program.len())
     }
 
-    fn print_output_with_instructions(&self) {
-        println!("{} - {}", self.get_output_string(), self.get_instructions());
-    }
+    /// Extends `a` by one octal digit so the program outputs `program[remaining - 1..]`.
+    fn search_quine(&mut self, program: &[usize], a: usize, remaining: usize) -> Option<usize> {
+        if remaining == 0 {
+            return Some(a);
+        }
 
-    fn find_quine(&self) -> Option<usize> {
-        let mut a_value = 0;
-        let mut match_index = 0;
-        let mut i = 0;
-        loop {
-            let test = a_value << 3 | i;
-            let mut computer = self.clone();
-            computer.state.register_a.value = test;
-            computer.run();
-            let output_values = computer.get_output_number();
-            let program_lines = computer.get_instructions_number();
-            // let output_match_index = output_values.len() - match_index - 1;
-            // let test_match_index = program_lines.len() - match_index - 1;
-            computer.print_output_with_instructions();
-
-            // println!("---");
-            // println!("[{}] {} - Checking at index: {}", i, test, test_match_index);
-            // println!(
-            //     "[{}] Expecting {} to match {}",
-            //     i, output_values[output_match_index], program_lines[test_match_index]
-            // );
-
-            // Work backwards from the end and make sure everything at the end matches
-            // If this exceeds the current match_index, then increment the match_index
-            // Continue until no match
-
-            let mut dec_match_index = 0;
-            let mut exceeded_previous_match = false;
-            while output_values[output_values.len() - 1 - dec_match_index]
-                == program_lines[program_lines.len() - 1 - dec_match_index]
+        let expected = &program[remaining - 1..];
+        (0..8).find_map(|digit| {
+            let candidate = a << 3 | digit;
+            // zero would halt the loop right away, so the highest digit is never zero.
+            if candidate == 0 || !self.run_with_a(candidate) || self.machine.cpu.output != expected
             {
-                dec_match_index += 1;
-                if dec_match_index >= program_lines.len() {
-                    return Some(test);
-                }
-                if dec_match_index > match_index {
-                    exceeded_previous_match = true;
-                    match_index += 1;
-                    i = 0;
-                    a_value = test;
-                }
-                if dec_match_index >= output_values.len() {
-                    break;
-                }
+                return None;
             }
-            if !exceeded_previous_match {
-                i += 1;
-            }
-        }
-
-        None
+            self.search_quine(program, candidate, remaining - 1)
+        })
     }
 }
 
 pub fn part_one(input: &str) -> Option<String> {
     let mut computer = Computer::parse_from_input(input);
-    computer.run();
+    if !computer.run() {
+        return None;
+    }
     Some(computer.get_output_string())
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
     let computer = Computer::parse_from_input(input);
+    // the quine search relies on the shape of the program, so show it when debugging.
+    viz!("Program:\n{}", computer.disassemble());
     computer.find_quine()
 }
 
@@ -376,4 +329,30 @@ mod tests_17 {
         ));
         assert_eq!(result, Some(117440));
     }
+
+    #[test]
+    fn test_disassemble() {
+        let computer = Computer::parse_from_input(&advent_of_code::template::read_file_part(
+            "examples", DAY, 2,
+        ));
+        assert_eq!(
+            computer.disassemble(),
+            "00: a = a >> 3\n02: out a % 8\n04: if a != 0 goto 0"
+        );
+    }
+
+    #[test]
+    fn test_no_quine() {
+        // only ever outputs 1, so no value for A reproduces the program.
+        let computer = Computer::parse_from_input(
+            "Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: 5,1,0,3,3,0",
+        );
+        assert_eq!(computer.find_quine(), None);
+
+        // jumps back to itself forever.
+        assert_eq!(
+            part_one("Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 3,0"),
+            None
+        );
+    }
 }
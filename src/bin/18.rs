@@ -1,129 +1,130 @@
-use std::{collections::VecDeque, usize};
+use advent_of_code::grid::{Grid, Pos};
+use advent_of_code::render::GifRenderer;
+use advent_of_code::union_find::UnionFind;
 
-advent_of_code::solution!(18);
+advent_of_code::solution!(18, variants {
+    2: "binary-search" => part_two_binary_search,
+});
 
-#[derive(Clone)]
-enum Cell {
-    Empty(usize),
-    Corrupted,
-}
+const NEVER: usize = usize::MAX;
 
-struct Grid {
-    width: usize,
-    height: usize,
-    falling_bytes: Vec<(usize, usize)>,
-    cells: Vec<Vec<Cell>>,
+struct MemorySpace {
+    falling_bytes: Vec<Pos>,
+    /// Index of the first byte that falls on each cell, `NEVER` if none does.
+    fallen_at: Grid<usize>,
 }
 
-impl Grid {
+impl MemorySpace {
     fn from_input(input: &str, width: usize, height: usize) -> Self {
-        let cells = vec![vec![Cell::Empty(usize::MAX); width]; height];
+        let mut fallen_at = Grid::new(width, height, NEVER);
         let mut falling_bytes = Vec::new();
         // Each line is a comma-separated coordinate pair that represents a falling byte.
-        for line in input.lines() {
-            let mut parts = line.split(',');
-            let x = parts.next().unwrap().parse().unwrap();
-            let y = parts.next().unwrap().parse().unwrap();
-            falling_bytes.push((x, y));
+        for (index, line) in input.lines().enumerate() {
+            let (x, y) = line.split_once(',').unwrap();
+            let pos = (x.parse().unwrap(), y.parse().unwrap());
+            fallen_at[pos] = fallen_at[pos].min(index);
+            falling_bytes.push(pos);
         }
-        Grid {
-            width,
-            height,
+        MemorySpace {
             falling_bytes,
-            cells,
+            fallen_at,
         }
     }
 
-    fn reset_empty_cells(&mut self) {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                if let Cell::Empty(steps) = self.cells[y][x] {
-                    self.cells[y][x] = Cell::Empty(usize::MAX);
-                }
-            }
-        }
+    fn exit(&self) -> Pos {
+        (self.fallen_at.width - 1, self.fallen_at.height - 1)
     }
 
-    fn set_cell(&mut self, x: usize, y: usize, cell: Cell) {
-        self.cells[y][x] = cell;
+    /// Whether the cell is still free after the first `num_bytes` bytes fell.
+    fn is_free(&self, pos: Pos, num_bytes: usize) -> bool {
+        self.fallen_at[pos] >= num_bytes
     }
 
-    fn cell_is_passable(&self, x: usize, y: usize) -> bool {
-        match self.cells[y][x] {
-            Cell::Empty(_) => true,
-            Cell::Corrupted => false,
-        }
+    fn min_steps(&self, num_bytes: usize) -> Option<usize> {
+        self.fallen_at
+            .shortest_path((0, 0), self.exit(), |pos, _| self.is_free(pos, num_bytes))
     }
 
-    fn simulate_falling_bytes(&mut self, num_bytes: usize) {
-        for index in 0..num_bytes {
-            let (x, y) = *self.falling_bytes.get(index).unwrap();
-            self.set_cell(x, y, Cell::Corrupted);
-        }
-    }
+    /// Index of the first byte that cuts the exit off.
+    ///
+    /// Starts with every byte fallen and lifts them again in reverse order, merging each freed cell with
+    /// its free neighbours. The first byte whose removal connects start and exit is the one that blocked them.
+    fn first_blocking_byte(&self) -> Option<usize> {
+        let grid = &self.fallen_at;
+        let index = |(x, y): Pos| y * grid.width + x;
+        let mut sets = UnionFind::new(grid.width * grid.height);
+        let free = |sets: &mut UnionFind, pos: Pos, num_bytes: usize| {
+            for next in grid.neighbours(pos) {
+                if self.is_free(next, num_bytes) {
+                    sets.union(index(pos), index(next));
+                }
+            }
+        };
 
-    fn find_min_steps_to_coord(&mut self, end_x: usize, end_y: usize) -> Option<usize> {
-        if end_x >= self.width || end_y >= self.height {
+        let all = self.falling_bytes.len();
+        for pos in grid.find_all(|&fallen_at| fallen_at == NEVER) {
+            free(&mut sets, pos, all);
+        }
+        let (start, exit) = (index((0, 0)), index(self.exit()));
+        if sets.connected(start, exit) {
             return None;
         }
-        let mut min_steps = usize::MAX;
-        let mut queue: VecDeque<(usize, usize, usize)> = VecDeque::new();
-        queue.push_back((0, 0, 0));
-        while let Some((x, y, steps)) = queue.pop_front() {
-            if x == end_x && y == end_y {
-                if steps < min_steps {
-                    min_steps = steps;
-                }
+
+        for (num_bytes, &pos) in self.falling_bytes.iter().enumerate().rev() {
+            // a cell hit more than once only frees up when its first byte is lifted.
+            if grid[pos] != num_bytes {
                 continue;
             }
-
-            if self.cell_is_passable(x, y) {
-                let cell_cost = match self.cells[y][x] {
-                    Cell::Empty(cost) => cost,
-                    Cell::Corrupted => usize::MAX,
-                };
-
-                if cell_cost <= steps {
-                    continue;
-                } else {
-                    self.set_cell(x, y, Cell::Empty(steps));
-                }
-
-                if x > 0 {
-                    queue.push_back((x - 1, y, steps + 1));
-                }
-                if x < self.width - 1 {
-                    queue.push_back((x + 1, y, steps + 1));
-                }
-                if y > 0 {
-                    queue.push_back((x, y - 1, steps + 1));
-                }
-                if y < self.height - 1 {
-                    queue.push_back((x, y + 1, steps + 1));
-                }
+            free(&mut sets, pos, num_bytes);
+            if sets.connected(start, exit) {
+                return Some(num_bytes);
             }
         }
+        None
+    }
 
-        if min_steps == usize::MAX {
-            None
-        } else {
-            Some(min_steps)
+    /// Same as [`Self::first_blocking_byte`], but searches the shortest byte prefix without a path.
+    fn first_blocking_byte_by_search(&self) -> Option<usize> {
+        let (mut reachable, mut blocked) = (0, self.falling_bytes.len());
+        if self.min_steps(blocked).is_some() {
+            return None;
+        }
+        while blocked - reachable > 1 {
+            let mid = (reachable + blocked) / 2;
+            if self.min_steps(mid).is_some() {
+                reachable = mid;
+            } else {
+                blocked = mid;
+            }
         }
+        Some(blocked - 1)
     }
 
-    fn find_first_blocking_byte(&mut self) -> Option<(usize, usize)> {
-        let cloned_bytes = self.falling_bytes.clone();
-        for (x, y) in cloned_bytes {
-            self.set_cell(x, y, Cell::Corrupted);
-            self.reset_empty_cells();
-            if self
-                .find_min_steps_to_coord(self.width - 1, self.height - 1)
-                .is_none()
-            {
-                return Some((x, y));
-            }
+    /// Records the bytes falling up to the blocking one, with the cells still reachable in the last frame.
+    fn record(&self, blocking: usize) {
+        let grid = &self.fallen_at;
+        let Some(mut gif) = GifRenderer::from_args(grid.width, grid.height, |options| {
+            options.scale(8).frame_skip(5)
+        }) else {
+            return;
+        };
+
+        for num_bytes in 1..=blocking {
+            gif.push(|x, y| palette_index(grid[(x, y)], num_bytes, false));
         }
-        None
+        let num_bytes = blocking + 1;
+        let reached = grid.distances((0, 0), |pos, _| self.is_free(pos, num_bytes));
+        gif.push_frame(|x, y| palette_index(grid[(x, y)], num_bytes, reached[(x, y)].is_some()));
+    }
+}
+
+/// Color of a cell in the default gif palette after `num_bytes` bytes fell, highlighting the last one.
+fn palette_index(fallen_at: usize, num_bytes: usize, reached: bool) -> u8 {
+    match fallen_at {
+        index if index + 1 == num_bytes => 4,
+        index if index < num_bytes => 1,
+        _ if reached => 3,
+        _ => 0,
     }
 }
 
@@ -133,19 +134,25 @@ pub fn part_one_internal(
     height: usize,
     num_bytes: usize,
 ) -> Option<usize> {
-    let mut grid = Grid::from_input(input, width + 1, height + 1);
-    grid.simulate_falling_bytes(num_bytes);
-    grid.find_min_steps_to_coord(width, height)
+    MemorySpace::from_input(input, width + 1, height + 1).min_steps(num_bytes)
+}
+
+fn format_byte(space: &MemorySpace, index: usize) -> String {
+    let (x, y) = space.falling_bytes[index];
+    format!("{},{}", x, y)
 }
 
 pub fn part_two_internal(input: &str, width: usize, height: usize) -> Option<String> {
-    let mut grid = Grid::from_input(input, width + 1, height + 1);
-    let blocking_byte = grid.find_first_blocking_byte();
-    if let Some((x, y)) = blocking_byte {
-        Some(format!("{},{}", x, y))
-    } else {
-        None
-    }
+    let space = MemorySpace::from_input(input, width + 1, height + 1);
+    let blocking = space.first_blocking_byte()?;
+    space.record(blocking);
+    Some(format_byte(&space, blocking))
+}
+
+pub fn part_two_search_internal(input: &str, width: usize, height: usize) -> Option<String> {
+    let space = MemorySpace::from_input(input, width + 1, height + 1);
+    let blocking = space.first_blocking_byte_by_search()?;
+    Some(format_byte(&space, blocking))
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
@@ -156,6 +163,10 @@ pub fn part_two(input: &str) -> Option<String> {
     part_two_internal(input, 70, 70)
 }
 
+pub fn part_two_binary_search(input: &str) -> Option<String> {
+    part_two_search_internal(input, 70, 70)
+}
+
 #[cfg(test)]
 mod tests_18 {
     use super::*;
@@ -173,4 +184,18 @@ mod tests_18 {
         let result = part_two_internal(&input, 6, 6);
         assert_eq!(result, Some("6,1".to_string()));
     }
+
+    #[test]
+    fn test_part_two_binary_search() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_two_search_internal(&input, 6, 6);
+        assert_eq!(result, Some("6,1".to_string()));
+    }
+
+    #[test]
+    fn test_never_blocked() {
+        let input = "1,1\n1,1\n0,1\n";
+        assert_eq!(part_two_internal(input, 2, 2), None);
+        assert_eq!(part_two_search_internal(input, 2, 2), None);
+    }
 }
@@ -0,0 +1,188 @@
+//! Path counting on directed acyclic graphs with nodes numbered `0..len`.
+//!
+//! [`Dag::count_paths`] visits every node once in reverse topological order and computes, for all nodes at
+//! the same time, how many distinct targets they reach and how many distinct paths lead to them.
+
+#[derive(Debug, Clone, Default)]
+pub struct Dag {
+    successors: Vec<Vec<usize>>,
+}
+
+impl Dag {
+    /// Creates a graph with `len` nodes and no edges.
+    #[must_use]
+    pub fn new(len: usize) -> Self {
+        Self {
+            successors: vec![vec![]; len],
+        }
+    }
+
+    /// Number of nodes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.successors.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.successors.is_empty()
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize) {
+        self.successors[from].push(to);
+    }
+
+    #[must_use]
+    pub fn successors(&self, node: usize) -> &[usize] {
+        &self.successors[node]
+    }
+
+    /// The nodes ordered so that every edge points forward, `None` if the graph has a cycle.
+    #[must_use]
+    pub fn topological_order(&self) -> Option<Vec<usize>> {
+        let mut in_degree = vec![0; self.len()];
+        for &to in self.successors.iter().flatten() {
+            in_degree[to] += 1;
+        }
+        let mut order: Vec<usize> = (0..self.len()).filter(|&n| in_degree[n] == 0).collect();
+        let mut i = 0;
+        while let Some(&node) = order.get(i) {
+            for &next in &self.successors[node] {
+                in_degree[next] -= 1;
+                if in_degree[next] == 0 {
+                    order.push(next);
+                }
+            }
+            i += 1;
+        }
+        (order.len() == self.len()).then_some(order)
+    }
+
+    /// Counts reachable targets and paths to them from every node.
+    ///
+    /// # Panics
+    /// If the graph has a cycle.
+    #[must_use]
+    pub fn count_paths(&self, targets: &[usize]) -> PathCounts {
+        let order = self.topological_order().expect("graph has a cycle");
+        self.count_paths_in_order(&order, targets)
+    }
+
+    /// Same as [`Self::count_paths`], with a topological order that is already known, e.g. when every
+    /// edge leads to a higher level.
+    #[must_use]
+    pub fn count_paths_in_order(&self, order: &[usize], targets: &[usize]) -> PathCounts {
+        let words = targets.len().div_ceil(64);
+        let mut target_bit = vec![None; self.len()];
+        for (bit, &target) in targets.iter().enumerate() {
+            target_bit[target] = Some(bit);
+        }
+
+        let mut paths = vec![0; self.len()];
+        let mut reachable = vec![0_u64; self.len() * words];
+        for &node in order.iter().rev() {
+            if let Some(bit) = target_bit[node] {
+                paths[node] = 1;
+                reachable[node * words + bit / 64] |= 1 << (bit % 64);
+            }
+            for &next in &self.successors[node] {
+                paths[node] += paths[next];
+                for word in 0..words {
+                    reachable[node * words + word] |= reachable[next * words + word];
+                }
+            }
+        }
+
+        PathCounts {
+            paths,
+            reachable,
+            words,
+        }
+    }
+}
+
+/// Result of [`Dag::count_paths`]. A path ends at the first target it reaches or continues to the next one,
+/// both count as separate paths.
+#[derive(Debug, Clone)]
+pub struct PathCounts {
+    paths: Vec<u64>,
+    /// One bitset of reachable targets per node, `words` long.
+    reachable: Vec<u64>,
+    words: usize,
+}
+
+impl PathCounts {
+    /// Number of distinct paths from `node` to any target, a target has one path to itself.
+    #[must_use]
+    pub fn paths(&self, node: usize) -> u64 {
+        self.paths[node]
+    }
+
+    /// Number of distinct targets reachable from `node`, including `node` itself.
+    #[must_use]
+    pub fn reachable(&self, node: usize) -> usize {
+        self.reachable[node * self.words..(node + 1) * self.words]
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Dag;
+
+    /// A diamond `0 -> {1, 2} -> 3` with an extra target `4` behind `2`.
+    fn diamond() -> Dag {
+        let mut dag = Dag::new(5);
+        dag.add_edge(0, 1);
+        dag.add_edge(0, 2);
+        dag.add_edge(1, 3);
+        dag.add_edge(2, 3);
+        dag.add_edge(2, 4);
+        dag
+    }
+
+    #[test]
+    fn counts_paths_and_targets() {
+        let counts = diamond().count_paths(&[3, 4]);
+        assert_eq!(counts.paths(0), 3);
+        assert_eq!(counts.reachable(0), 2);
+        assert_eq!(counts.paths(1), 1);
+        assert_eq!(counts.reachable(1), 1);
+        assert_eq!(counts.paths(3), 1);
+
+        let only_three = diamond().count_paths(&[3]);
+        assert_eq!(only_three.paths(0), 2);
+        assert_eq!(only_three.reachable(4), 0);
+    }
+
+    #[test]
+    fn orders_topologically() {
+        let dag = diamond();
+        let order = dag.topological_order().unwrap();
+        let position = |n| order.iter().position(|&o| o == n).unwrap();
+        for node in 0..dag.len() {
+            for &next in dag.successors(node) {
+                assert!(position(node) < position(next));
+            }
+        }
+
+        let mut cyclic = diamond();
+        cyclic.add_edge(3, 0);
+        assert_eq!(cyclic.topological_order(), None);
+    }
+
+    #[test]
+    fn tracks_many_targets() {
+        // a fan from node 0 to 100 targets needs more than one bitset word.
+        let mut dag = Dag::new(101);
+        for target in 1..=100 {
+            dag.add_edge(0, target);
+        }
+        let targets: Vec<_> = (1..=100).collect();
+        let counts = dag.count_paths(&targets);
+        assert_eq!(counts.reachable(0), 100);
+        assert_eq!(counts.paths(0), 100);
+    }
+}
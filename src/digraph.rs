@@ -0,0 +1,278 @@
+//! Directed graphs over hashable node values, for ordering problems.
+//!
+//! Offers a topological sort that breaks ties by the smallest node, cycle detection that returns the
+//! offending cycle, ordering of an induced subgraph and a comparator for `sort_by` built from the edges.
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::hash::Hash;
+
+/// Nodes that depend on each other in a circle, each one has an edge to the next and the last one
+/// back to the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<N>(pub Vec<N>);
+
+#[derive(Debug, Clone)]
+pub struct DiGraph<N> {
+    nodes: Vec<N>,
+    index: HashMap<N, usize>,
+    successors: Vec<Vec<usize>>,
+    predecessors: Vec<Vec<usize>>,
+    edges: HashSet<(usize, usize)>,
+}
+
+impl<N> Default for DiGraph<N> {
+    fn default() -> Self {
+        Self {
+            nodes: vec![],
+            index: HashMap::new(),
+            successors: vec![],
+            predecessors: vec![],
+            edges: HashSet::new(),
+        }
+    }
+}
+
+impl<N: Clone + Eq + Hash + Ord> DiGraph<N> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a graph from `(from, to)` edges, adding their nodes as needed.
+    pub fn from_edges(edges: impl IntoIterator<Item = (N, N)>) -> Self {
+        let mut graph = Self::new();
+        for (from, to) in edges {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+
+    /// Adds a node if it is not part of the graph yet.
+    pub fn add_node(&mut self, node: N) {
+        self.id(node);
+    }
+
+    fn id(&mut self, node: N) -> usize {
+        if let Some(&id) = self.index.get(&node) {
+            return id;
+        }
+        let id = self.nodes.len();
+        self.index.insert(node.clone(), id);
+        self.nodes.push(node);
+        self.successors.push(vec![]);
+        self.predecessors.push(vec![]);
+        id
+    }
+
+    /// Adds an edge from `from` to `to`, meaning `from` has to come before `to`. Duplicates are ignored.
+    pub fn add_edge(&mut self, from: N, to: N) {
+        let (from, to) = (self.id(from), self.id(to));
+        if self.edges.insert((from, to)) {
+            self.successors[from].push(to);
+            self.predecessors[to].push(from);
+        }
+    }
+
+    /// Number of nodes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    #[must_use]
+    pub fn contains(&self, node: &N) -> bool {
+        self.index.contains_key(node)
+    }
+
+    #[must_use]
+    pub fn has_edge(&self, from: &N, to: &N) -> bool {
+        match (self.index.get(from), self.index.get(to)) {
+            (Some(&from), Some(&to)) => self.edges.contains(&(from, to)),
+            _ => false,
+        }
+    }
+
+    /// Nodes in the order they were added.
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.nodes.iter()
+    }
+
+    /// Nodes with an edge from `node`, in the order the edges were added.
+    pub fn successors(&self, node: &N) -> impl Iterator<Item = &N> {
+        self.index
+            .get(node)
+            .into_iter()
+            .flat_map(|&id| &self.successors[id])
+            .map(|&id| &self.nodes[id])
+    }
+
+    /// All nodes ordered so that every edge points forward, using Kahn's algorithm.
+    /// Whenever several nodes are ready, the smallest one comes first, so the order is deterministic.
+    ///
+    /// # Errors
+    /// If the graph contains a cycle.
+    pub fn toposort(&self) -> Result<Vec<N>, Cycle<N>> {
+        let mut in_degree: Vec<usize> = self.predecessors.iter().map(Vec::len).collect();
+        let mut ready: BinaryHeap<_> = (0..self.len())
+            .filter(|&id| in_degree[id] == 0)
+            .map(|id| Reverse((&self.nodes[id], id)))
+            .collect();
+
+        let mut sorted = Vec::with_capacity(self.len());
+        while let Some(Reverse((node, id))) = ready.pop() {
+            sorted.push(node.clone());
+            for &next in &self.successors[id] {
+                in_degree[next] -= 1;
+                if in_degree[next] == 0 {
+                    ready.push(Reverse((&self.nodes[next], next)));
+                }
+            }
+        }
+
+        if sorted.len() == self.len() {
+            Ok(sorted)
+        } else {
+            Err(self.cycle_among(&in_degree))
+        }
+    }
+
+    /// Every node Kahn's algorithm could not remove still has a predecessor that was not removed either,
+    /// so walking backwards from one of them has to run into a cycle.
+    fn cycle_among(&self, in_degree: &[usize]) -> Cycle<N> {
+        let remaining = |id: usize| in_degree[id] > 0;
+        let mut seen_at = vec![None; self.len()];
+        let mut walk = vec![];
+        let mut current = (0..self.len()).find(|&id| remaining(id)).unwrap();
+
+        while seen_at[current].is_none() {
+            seen_at[current] = Some(walk.len());
+            walk.push(current);
+            current = self.predecessors[current]
+                .iter()
+                .copied()
+                .find(|&id| remaining(id))
+                .unwrap();
+        }
+
+        let start = seen_at[current].unwrap();
+        Cycle(
+            walk[start..]
+                .iter()
+                .rev()
+                .map(|&id| self.nodes[id].clone())
+                .collect(),
+        )
+    }
+
+    /// The first cycle found in the graph, if there is one.
+    #[must_use]
+    pub fn find_cycle(&self) -> Option<Cycle<N>> {
+        self.toposort().err()
+    }
+
+    /// The graph restricted to `nodes` and the edges between them. Nodes that are not part of the graph
+    /// are added without edges.
+    #[must_use]
+    pub fn subgraph(&self, nodes: &[N]) -> Self {
+        let mut subgraph = Self::new();
+        for node in nodes {
+            subgraph.add_node(node.clone());
+        }
+        for from in nodes {
+            for to in self.successors(from) {
+                if subgraph.contains(to) {
+                    subgraph.add_edge(from.clone(), to.clone());
+                }
+            }
+        }
+        subgraph
+    }
+
+    /// Topological order of `nodes`, only considering the edges between them.
+    ///
+    /// # Errors
+    /// If the induced subgraph contains a cycle.
+    pub fn order_subset(&self, nodes: &[N]) -> Result<Vec<N>, Cycle<N>> {
+        self.subgraph(nodes).toposort()
+    }
+
+    /// Whether no edge between two of the `nodes` points backwards in the slice.
+    #[must_use]
+    pub fn is_ordered(&self, nodes: &[N]) -> bool {
+        let position: HashMap<&N, usize> = nodes.iter().enumerate().map(|(i, n)| (n, i)).collect();
+        nodes.iter().enumerate().all(|(i, node)| {
+            self.successors(node)
+                .all(|next| position.get(next).is_none_or(|&j| j > i))
+        })
+    }
+
+    /// Orders two nodes by the edge between them: `Less` if `a` has to come first, `Greater` if `b` has,
+    /// `Equal` if there is no direct edge.
+    ///
+    /// This is only a total order if every pair of compared nodes is connected by an edge, as with
+    /// exhaustive pairwise rules. Otherwise use [`Self::order_subset`].
+    #[must_use]
+    pub fn compare(&self, a: &N, b: &N) -> Ordering {
+        if self.has_edge(a, b) {
+            Ordering::Less
+        } else if self.has_edge(b, a) {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }
+
+    /// [`Self::compare`] as a closure for `sort_by`.
+    pub fn comparator(&self) -> impl Fn(&N, &N) -> Ordering + '_ {
+        |a, b| self.compare(a, b)
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Cycle, DiGraph};
+
+    #[test]
+    fn sorts_topologically() {
+        let graph =
+            DiGraph::from_edges([(5, 11), (7, 11), (7, 8), (3, 8), (3, 10), (11, 2), (11, 9)]);
+        assert_eq!(graph.toposort(), Ok(vec![3, 5, 7, 8, 10, 11, 2, 9]));
+        assert_eq!(graph.find_cycle(), None);
+        assert_eq!(graph.len(), 8);
+        assert!(graph.has_edge(&7, &8));
+        assert!(!graph.has_edge(&8, &7));
+    }
+
+    #[test]
+    fn reports_cycles() {
+        let graph = DiGraph::from_edges([(1, 2), (2, 3), (3, 4), (4, 2), (0, 1)]);
+        let Some(Cycle(cycle)) = graph.find_cycle() else {
+            panic!("expected a cycle");
+        };
+        assert_eq!(cycle.len(), 3);
+        for (i, from) in cycle.iter().enumerate() {
+            assert!(graph.has_edge(from, &cycle[(i + 1) % cycle.len()]));
+        }
+    }
+
+    #[test]
+    fn orders_subsets() {
+        // the full rule set is cyclic, but every update only touches part of it.
+        let graph = DiGraph::from_edges([(1, 2), (2, 3), (3, 1)]);
+        assert!(graph.toposort().is_err());
+        assert_eq!(graph.order_subset(&[3, 2]), Ok(vec![2, 3]));
+        assert_eq!(graph.order_subset(&[3, 9, 1]), Ok(vec![3, 1, 9]));
+
+        assert!(graph.is_ordered(&[1, 2]));
+        assert!(!graph.is_ordered(&[2, 4, 1]));
+
+        let mut update = vec![3, 2];
+        update.sort_by(graph.comparator());
+        assert_eq!(update, [2, 3]);
+    }
+}
@@ -0,0 +1,295 @@
+//! A dense two-dimensional grid addressed by `(x, y)` positions, with `(0, 0)` in the top-left corner.
+use std::collections::VecDeque;
+use std::ops::{Index, IndexMut};
+
+use crate::lattice::{Bounds, Vector};
+
+/// A position on a [`Grid`] as `(x, y)`.
+pub type Pos = (usize, usize);
+
+/// The four orthogonal directions, with `Up` pointing towards row `0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// All directions in clockwise order, starting with `Up`.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// The `(dx, dy)` offset of a single step.
+    #[must_use]
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Right => (1, 0),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+        }
+    }
+
+    #[must_use]
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    #[must_use]
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    #[must_use]
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    pub width: usize,
+    pub height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// Creates a grid with every cell set to `value`.
+    #[must_use]
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid by calling `cell` for every position in row-major order.
+    pub fn from_fn(width: usize, height: usize, mut cell: impl FnMut(Pos) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(&mut cell)
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parses one row per line, mapping every character with `cell`.
+    ///
+    /// # Panics
+    /// If the lines differ in length.
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> T) -> Self {
+        let mut width = 0;
+        let mut height = 0;
+        let mut cells = vec![];
+        for line in input.lines().filter(|line| !line.is_empty()) {
+            let before = cells.len();
+            cells.extend(line.chars().map(&mut cell));
+            if height == 0 {
+                width = cells.len();
+            }
+            assert_eq!(
+                cells.len() - before,
+                width,
+                "row {height} has a different width"
+            );
+            height += 1;
+        }
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    #[must_use]
+    pub fn contains(&self, (x, y): Pos) -> bool {
+        x < self.width && y < self.height
+    }
+
+    #[must_use]
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.cells[pos.1 * self.width + pos.0])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.contains(pos) {
+            Some(&mut self.cells[pos.1 * self.width + pos.0])
+        } else {
+            None
+        }
+    }
+
+    /// The position one step from `pos` in `direction`, or `None` if it leaves the grid.
+    #[must_use]
+    pub fn step(&self, pos: Pos, direction: Direction) -> Option<Pos> {
+        self.offset(pos, direction.offset())
+    }
+
+    /// The position `pos + (dx, dy)`, or `None` if it lies outside the grid.
+    #[must_use]
+    pub fn offset(&self, pos: Pos, offset: Vector) -> Option<Pos> {
+        Bounds::from(self).offset(pos, offset)
+    }
+
+    /// The orthogonal neighbours of `pos` that lie inside the grid.
+    pub fn neighbours(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| self.step(pos, direction))
+    }
+
+    /// All positions in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// All cells with their position in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Positions of all cells for which `predicate` holds.
+    pub fn find_all<'a>(
+        &'a self,
+        predicate: impl Fn(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = Pos> + 'a {
+        self.iter()
+            .filter(move |(_, cell)| predicate(cell))
+            .map(|(pos, _)| pos)
+    }
+
+    /// Maps every cell to a new grid of the same size.
+    pub fn map<U>(&self, mut f: impl FnMut(Pos, &T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.iter().map(|(pos, cell)| f(pos, cell)).collect(),
+        }
+    }
+
+    /// Breadth-first step counts from `start` to every cell reachable through cells for which `passable`
+    /// holds. Unreachable cells are `None`, and so is `start` if it is not passable itself.
+    pub fn distances(&self, start: Pos, passable: impl Fn(Pos, &T) -> bool) -> Grid<Option<usize>> {
+        let mut distances = Grid::from_fn(self.width, self.height, |_| None);
+        if !self.get(start).is_some_and(|cell| passable(start, cell)) {
+            return distances;
+        }
+
+        distances[start] = Some(0);
+        let mut queue = VecDeque::from([(start, 0)]);
+        while let Some((pos, steps)) = queue.pop_front() {
+            for next in self.neighbours(pos) {
+                if distances[next].is_none() && passable(next, &self[next]) {
+                    distances[next] = Some(steps + 1);
+                    queue.push_back((next, steps + 1));
+                }
+            }
+        }
+        distances
+    }
+
+    /// Number of steps on the shortest path from `start` to `goal` through passable cells.
+    #[must_use]
+    pub fn shortest_path(
+        &self,
+        start: Pos,
+        goal: Pos,
+        passable: impl Fn(Pos, &T) -> bool,
+    ) -> Option<usize> {
+        self.distances(start, passable).get(goal).copied().flatten()
+    }
+
+    /// Renders one line per row, mapping every cell with `cell`.
+    pub fn render(&self, mut cell: impl FnMut(Pos, &T) -> char) -> String {
+        let mut output = String::with_capacity((self.width + 1) * self.height);
+        for (pos, value) in self.iter() {
+            if pos.0 == 0 && pos.1 > 0 {
+                output.push('\n');
+            }
+            output.push(cell(pos, value));
+        }
+        output
+    }
+}
+
+impl Grid<char> {
+    /// Parses a grid of plain characters.
+    #[must_use]
+    pub fn from_chars(input: &str) -> Self {
+        Self::parse(input, |c| c)
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside of the grid"))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside of the grid"))
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Direction, Grid};
+
+    #[test]
+    fn parses_and_indexes() {
+        let grid = Grid::from_chars("ab\ncd\nef\n");
+        assert_eq!((grid.width, grid.height), (2, 3));
+        assert_eq!(grid[(1, 2)], 'f');
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.find_all(|&c| c == 'c').collect::<Vec<_>>(), [(0, 1)]);
+        assert_eq!(grid.render(|_, &c| c), "ab\ncd\nef");
+    }
+
+    #[test]
+    fn steps_inside_bounds() {
+        let grid = Grid::new(3, 3, 0);
+        assert_eq!(grid.step((0, 0), Direction::Up), None);
+        assert_eq!(grid.step((0, 0), Direction::Right), Some((1, 0)));
+        assert_eq!(grid.neighbours((1, 1)).count(), 4);
+        assert_eq!(
+            grid.neighbours((2, 2)).collect::<Vec<_>>(),
+            [(2, 1), (1, 2)]
+        );
+        assert_eq!(Direction::Left.turn_right(), Direction::Up);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Down.opposite(), Direction::Up);
+    }
+
+    #[test]
+    fn finds_shortest_paths() {
+        let grid = Grid::parse("..#\n#..\n.#.\n", |c| c == '#');
+        let distances = grid.distances((0, 0), |_, &wall| !wall);
+        assert_eq!(distances[(2, 2)], Some(4));
+        assert_eq!(distances[(0, 2)], None);
+        assert_eq!(
+            grid.shortest_path((0, 0), (2, 1), |_, &wall| !wall),
+            Some(3)
+        );
+        assert_eq!(grid.shortest_path((0, 0), (0, 2), |_, &wall| !wall), None);
+        assert_eq!(grid.shortest_path((2, 0), (0, 0), |_, &wall| !wall), None);
+    }
+}
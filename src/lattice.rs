@@ -0,0 +1,138 @@
+//! Geometry on integer grid points: grouping points by label, pairs of points and the lattice points on
+//! the line through two of them.
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::grid::{Grid, Pos};
+use crate::linear::gcd;
+
+/// A `(dx, dy)` offset between two positions.
+pub type Vector = (isize, isize);
+
+/// The rectangle `0..width` by `0..height` that positions have to stay in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Bounds {
+    #[must_use]
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height }
+    }
+
+    #[must_use]
+    pub fn contains(&self, (x, y): Pos) -> bool {
+        x < self.width && y < self.height
+    }
+
+    /// The position `pos + vector`, or `None` if it lies outside the bounds.
+    #[must_use]
+    pub fn offset(&self, (x, y): Pos, (dx, dy): Vector) -> Option<Pos> {
+        let next = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        self.contains(next).then_some(next)
+    }
+
+    /// `start`, `start + step`, `start + 2 * step` and so on, as long as they are inside the bounds.
+    /// A zero step only yields `start`.
+    pub fn ray(&self, start: Pos, step: Vector) -> impl Iterator<Item = Pos> + '_ {
+        let first = self.contains(start).then_some(start);
+        std::iter::successors(first, move |&pos| {
+            if step == (0, 0) {
+                None
+            } else {
+                self.offset(pos, step)
+            }
+        })
+    }
+
+    /// Every lattice point inside the bounds on the line through `a` and `b`, in order along the line.
+    ///
+    /// The step between the points is reduced by the gcd of its components, so points between `a` and
+    /// `b` are included as well. If `a` and `b` are equal, only `a` is yielded.
+    pub fn line_through(&self, a: Pos, b: Pos) -> impl Iterator<Item = Pos> + '_ {
+        let (dx, dy) = reduced(difference(a, b));
+        let backwards: Vec<Pos> = self.ray(a, (-dx, -dy)).skip(1).collect();
+        backwards.into_iter().rev().chain(self.ray(a, (dx, dy)))
+    }
+}
+
+impl<T> From<&Grid<T>> for Bounds {
+    fn from(grid: &Grid<T>) -> Self {
+        Self::new(grid.width, grid.height)
+    }
+}
+
+/// The vector from `from` to `to`.
+#[must_use]
+pub fn difference(from: Pos, to: Pos) -> Vector {
+    (
+        to.0 as isize - from.0 as isize,
+        to.1 as isize - from.1 as isize,
+    )
+}
+
+/// The shortest vector pointing in the same direction with integer components.
+#[must_use]
+pub fn reduced((dx, dy): Vector) -> Vector {
+    let divisor = gcd(dx as i128, dy as i128).max(1) as isize;
+    (dx / divisor, dy / divisor)
+}
+
+/// Groups labelled points, keeping the order in which they are given within every group.
+pub fn group_by_label<L: Eq + Hash>(
+    points: impl IntoIterator<Item = (Pos, L)>,
+) -> HashMap<L, Vec<Pos>> {
+    let mut groups: HashMap<L, Vec<Pos>> = HashMap::new();
+    for (pos, label) in points {
+        groups.entry(label).or_default().push(pos);
+    }
+    groups
+}
+
+/// Every unordered pair of distinct items, `(items[i], items[j])` with `i < j`.
+pub fn pairs<T>(items: &[T]) -> impl Iterator<Item = (&T, &T)> {
+    items
+        .iter()
+        .enumerate()
+        .flat_map(move |(i, a)| items[i + 1..].iter().map(move |b| (a, b)))
+}
+
+/// Number of distinct points.
+pub fn count_unique(points: impl IntoIterator<Item = Pos>) -> usize {
+    points.into_iter().collect::<HashSet<_>>().len()
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{count_unique, difference, group_by_label, pairs, reduced, Bounds};
+
+    #[test]
+    fn groups_and_pairs_points() {
+        let groups = group_by_label([((0, 0), 'a'), ((1, 2), 'b'), ((3, 1), 'a')]);
+        assert_eq!(groups[&'a'], [(0, 0), (3, 1)]);
+        assert_eq!(groups[&'b'], [(1, 2)]);
+
+        let all: Vec<_> = pairs(&[1, 2, 3]).map(|(&a, &b)| (a, b)).collect();
+        assert_eq!(all, [(1, 2), (1, 3), (2, 3)]);
+        assert_eq!(pairs(&[1]).count(), 0);
+
+        assert_eq!(count_unique([(0, 0), (1, 1), (0, 0)]), 2);
+    }
+
+    #[test]
+    fn steps_along_lines() {
+        let bounds = Bounds::new(10, 10);
+        assert_eq!(difference((4, 2), (1, 8)), (-3, 6));
+        assert_eq!(reduced((-3, 6)), (-1, 2));
+        assert_eq!(reduced((0, -4)), (0, -1));
+        assert_eq!(bounds.offset((0, 3), (-1, 0)), None);
+
+        let line: Vec<_> = bounds.line_through((2, 2), (6, 4)).collect();
+        assert_eq!(line, [(0, 1), (2, 2), (4, 3), (6, 4), (8, 5)]);
+        assert_eq!(bounds.line_through((5, 5), (5, 5)).count(), 1);
+        assert_eq!(bounds.ray((9, 0), (1, 1)).count(), 1);
+        assert_eq!(bounds.ray((10, 0), (-1, 0)).count(), 0);
+    }
+}
@@ -1,3 +1,20 @@
+pub mod dag;
+pub mod digraph;
+pub mod grid;
+pub mod lattice;
+pub mod linear;
+pub mod memo;
+pub mod monotonic;
+pub mod pattern;
+pub mod push;
+pub mod regions;
+pub mod render;
+pub mod spans;
+pub mod swarm;
 pub mod template;
+pub mod union_find;
+pub mod viz;
+pub mod vm;
+pub mod walker;
 
 // Use this file to add helper functions and additional modules.
@@ -0,0 +1,373 @@
+//! Exact solvers for small linear systems and linear Diophantine equations.
+//!
+//! Everything is computed on `i128` rationals, so there is no rounding and no division by zero when a
+//! system is degenerate. Degenerate systems are reported as [`Solution::Infinite`] or [`Solution::None`]
+//! and can be resolved with [`min_cost_non_negative`].
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+#[must_use]
+pub fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// The smallest non-negative common multiple, `0` if either value is zero.
+#[must_use]
+pub fn lcm(a: i128, b: i128) -> i128 {
+    if a == 0 || b == 0 {
+        0
+    } else {
+        (a / gcd(a, b) * b).abs()
+    }
+}
+
+/// Returns `(g, x, y)` with `a * x + b * y = g`, where `g` is the non-negative gcd of `a` and `b`.
+#[must_use]
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// A fraction in lowest terms with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { num: 0, den: 1 };
+    pub const ONE: Rational = Rational { num: 1, den: 1 };
+
+    /// # Panics
+    /// If `den` is zero.
+    #[must_use]
+    pub fn new(num: i128, den: i128) -> Self {
+        assert_ne!(den, 0, "denominator must not be zero");
+        let g = gcd(num, den).max(1) * den.signum();
+        Self {
+            num: num / g,
+            den: den / g,
+        }
+    }
+
+    #[must_use]
+    pub fn numerator(self) -> i128 {
+        self.num
+    }
+
+    #[must_use]
+    pub fn denominator(self) -> i128 {
+        self.den
+    }
+
+    #[must_use]
+    pub fn is_zero(self) -> bool {
+        self.num == 0
+    }
+
+    /// The value as an integer, if it is one.
+    #[must_use]
+    pub fn to_integer(self) -> Option<i128> {
+        (self.den == 1).then_some(self.num)
+    }
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(value: i128) -> Self {
+        Self { num: value, den: 1 }
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.num * rhs.den + rhs.num * self.den, self.den * rhs.den)
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(self.num * rhs.num, self.den * rhs.den)
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    /// # Panics
+    /// If `rhs` is zero.
+    fn div(self, rhs: Self) -> Self {
+        Self::new(self.num * rhs.den, self.den * rhs.num)
+    }
+}
+
+/// The solutions of a linear system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Solution<const N: usize> {
+    Unique([Rational; N]),
+    /// The equations are dependent, e.g. two buttons that move in the same direction.
+    Infinite,
+    /// The equations contradict each other.
+    None,
+}
+
+impl<const N: usize> Solution<N> {
+    /// The unique solution, if all of its values are integers.
+    #[must_use]
+    pub fn integers(&self) -> Option<[i128; N]> {
+        match self {
+            Solution::Unique(values) => {
+                let mut integers = [0; N];
+                for (integer, value) in integers.iter_mut().zip(values) {
+                    *integer = value.to_integer()?;
+                }
+                Some(integers)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Solves `matrix * x = rhs` by Gaussian elimination, e.g. for 2x2 or 3x3 systems.
+#[must_use]
+pub fn solve<const N: usize>(matrix: [[i128; N]; N], rhs: [i128; N]) -> Solution<N> {
+    let mut rows: Vec<Vec<Rational>> = matrix
+        .iter()
+        .zip(rhs)
+        .map(|(row, value)| {
+            row.iter()
+                .copied()
+                .chain([value])
+                .map(Rational::from)
+                .collect()
+        })
+        .collect();
+
+    // reduced row echelon form, `pivots[i]` is the column of the pivot in row `i`.
+    let mut pivots = vec![];
+    for column in 0..N {
+        let row = pivots.len();
+        let Some(pivot) = (row..N).find(|&r| !rows[r][column].is_zero()) else {
+            continue;
+        };
+        rows.swap(row, pivot);
+
+        let factor = rows[row][column];
+        for value in &mut rows[row] {
+            *value = *value / factor;
+        }
+        let pivot_row = rows[row].clone();
+        for (other, values) in rows.iter_mut().enumerate() {
+            let factor = values[column];
+            if other == row || factor.is_zero() {
+                continue;
+            }
+            for (value, pivot_value) in values.iter_mut().zip(&pivot_row).skip(column) {
+                *value = *value - factor * *pivot_value;
+            }
+        }
+        pivots.push(column);
+    }
+
+    // a zero row with a non-zero right-hand side reads `0 = c`.
+    if rows[pivots.len()..].iter().any(|row| !row[N].is_zero()) {
+        return Solution::None;
+    }
+    if pivots.len() < N {
+        return Solution::Infinite;
+    }
+
+    let mut values = [Rational::ZERO; N];
+    for (value, row) in values.iter_mut().zip(&rows) {
+        *value = row[N];
+    }
+    Solution::Unique(values)
+}
+
+/// All integer solutions of `a * x + b * y = c`: `x = x0 + k * dx` and `y = y0 - k * dy` for any integer `k`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Diophantine {
+    pub x0: i128,
+    pub y0: i128,
+    pub dx: i128,
+    pub dy: i128,
+}
+
+impl Diophantine {
+    #[must_use]
+    pub fn at(&self, k: i128) -> (i128, i128) {
+        (self.x0 + k * self.dx, self.y0 - k * self.dy)
+    }
+}
+
+/// Solves `a * x + b * y = c` over the integers. Returns `None` if there is no solution,
+/// or if `a` and `b` are both zero.
+#[must_use]
+pub fn solve_diophantine(a: i128, b: i128, c: i128) -> Option<Diophantine> {
+    let (g, x, y) = extended_gcd(a, b);
+    if g == 0 || c % g != 0 {
+        return None;
+    }
+    let scale = c / g;
+    Some(Diophantine {
+        x0: x * scale,
+        y0: y * scale,
+        dx: b / g,
+        dy: a / g,
+    })
+}
+
+/// Non-negative integers `(x, y)` with `a * x + b * y = c` that minimise `cost_x * x + cost_y * y`.
+///
+/// # Panics
+/// If `a` or `b` is not positive.
+#[must_use]
+pub fn min_cost_non_negative(
+    a: i128,
+    b: i128,
+    c: i128,
+    cost_x: i128,
+    cost_y: i128,
+) -> Option<(i128, i128)> {
+    assert!(a > 0 && b > 0, "coefficients must be positive");
+    let solutions = solve_diophantine(a, b, c)?;
+
+    // x >= 0 and y >= 0 bound `k` from both sides, since `dx` and `dy` are positive.
+    let k_min = div_ceil(-solutions.x0, solutions.dx);
+    let k_max = div_floor(solutions.y0, solutions.dy);
+    if k_min > k_max {
+        return None;
+    }
+
+    // the cost is linear in `k`, so the optimum is at one of the bounds.
+    let slope = cost_x * solutions.dx - cost_y * solutions.dy;
+    Some(solutions.at(if slope > 0 { k_min } else { k_max }))
+}
+
+fn div_floor(a: i128, b: i128) -> i128 {
+    let q = a / b;
+    if a % b != 0 && (a < 0) != (b < 0) {
+        q - 1
+    } else {
+        q
+    }
+}
+
+fn div_ceil(a: i128, b: i128) -> i128 {
+    -div_floor(-a, b)
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        extended_gcd, lcm, min_cost_non_negative, solve, solve_diophantine, Rational, Solution,
+    };
+
+    #[test]
+    fn reduces_rationals() {
+        assert_eq!(Rational::new(4, -6), Rational::new(-2, 3));
+        assert_eq!(
+            Rational::new(1, 2) + Rational::new(1, 3),
+            Rational::new(5, 6)
+        );
+        assert_eq!(
+            (Rational::new(3, 4) / Rational::new(3, 2)).to_string(),
+            "1/2"
+        );
+        assert_eq!(Rational::new(6, 3).to_integer(), Some(2));
+    }
+
+    #[test]
+    fn solves_systems() {
+        let solution = solve([[94, 22], [34, 67]], [8400, 5400]);
+        assert_eq!(solution.integers(), Some([80, 40]));
+
+        let fractional = solve([[2, 0], [0, 4]], [1, 2]);
+        assert_eq!(
+            fractional,
+            Solution::Unique([Rational::new(1, 2), Rational::new(1, 2)])
+        );
+        assert_eq!(fractional.integers(), None);
+
+        let three = solve([[1, 1, 1], [0, 2, 5], [2, 5, -1]], [6, -4, 27]);
+        assert_eq!(three.integers(), Some([5, 3, -2]));
+
+        assert_eq!(solve([[1, 2], [2, 4]], [3, 6]), Solution::Infinite);
+        assert_eq!(solve([[1, 2], [2, 4]], [3, 7]), Solution::None);
+    }
+
+    #[test]
+    fn solves_diophantine_equations() {
+        assert_eq!(extended_gcd(240, 46), (2, -9, 47));
+        assert_eq!(lcm(101, 103), 10403);
+        assert_eq!(lcm(-4, 6), 12);
+
+        let solutions = solve_diophantine(6, 10, 8).unwrap();
+        for k in -3..3 {
+            let (x, y) = solutions.at(k);
+            assert_eq!(6 * x + 10 * y, 8);
+        }
+        assert!(solve_diophantine(6, 10, 7).is_none());
+
+        // 2 * x + 1 * y = 5: `y` is cheaper per unit, `x` is cheaper per press.
+        assert_eq!(min_cost_non_negative(2, 1, 5, 3, 1), Some((0, 5)));
+        assert_eq!(min_cost_non_negative(2, 1, 5, 1, 1), Some((2, 1)));
+        assert_eq!(min_cost_non_negative(4, 6, 1, 1, 1), None);
+        assert_eq!(min_cost_non_negative(5, 7, 3, 1, 1), None);
+    }
+}
@@ -1,4 +1,6 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
+use advent_of_code::template::commands::{
+    all, download, read, readme, scaffold, solve, status, time, watch,
+};
 use args::{parse, AppArguments};
 
 #[cfg(feature = "today")]
@@ -7,6 +9,7 @@ use advent_of_code::template::Day;
 use std::process;
 
 mod args {
+    use advent_of_code::template::bench_report::{parse_list, Format, ReportOptions};
     use advent_of_code::template::Day;
     use std::process;
 
@@ -26,15 +29,30 @@ mod args {
             day: Day,
             release: bool,
             dhat: bool,
+            checked: bool,
+            viz: bool,
+            gif: Option<String>,
+            variant: Option<String>,
             submit: Option<u8>,
         },
         All {
             release: bool,
+            checked: bool,
         },
         Time {
             all: bool,
             day: Option<Day>,
             store: bool,
+            memory: bool,
+            variants: bool,
+            options: ReportOptions,
+            formats: Vec<Format>,
+        },
+        Status,
+        Readme,
+        Watch {
+            day: Day,
+            release: bool,
         },
         #[cfg(feature = "today")]
         Today,
@@ -46,17 +64,40 @@ mod args {
         let app_args = match args.subcommand()?.as_deref() {
             Some("all") => AppArguments::All {
                 release: args.contains("--release"),
+                checked: args.contains("--checked"),
             },
             Some("time") => {
                 let all = args.contains("--all");
                 let store = args.contains("--store");
+                let memory = args.contains("--memory");
+                let variants = args.contains("--variants");
+
+                let defaults = ReportOptions::default();
+                let options = ReportOptions {
+                    columns: args
+                        .opt_value_from_fn("--columns", parse_list)?
+                        .unwrap_or(defaults.columns),
+                    sort: args.opt_value_from_str("--sort")?.unwrap_or(defaults.sort),
+                };
 
                 AppArguments::Time {
                     all,
-                    day: args.opt_free_from_str()?,
                     store,
+                    memory,
+                    variants,
+                    options,
+                    formats: args
+                        .opt_value_from_fn("--format", parse_list)?
+                        .unwrap_or_default(),
+                    day: args.opt_free_from_str()?,
                 }
             }
+            Some("status") => AppArguments::Status,
+            Some("readme") => AppArguments::Readme,
+            Some("watch-day") => AppArguments::Watch {
+                day: args.free_from_str()?,
+                release: args.contains("--release"),
+            },
             Some("download") => AppArguments::Download {
                 day: args.free_from_str()?,
             },
@@ -68,12 +109,25 @@ mod args {
                 download: args.contains("--download"),
                 overwrite: args.contains("--overwrite"),
             },
-            Some("solve") => AppArguments::Solve {
-                day: args.free_from_str()?,
-                release: args.contains("--release"),
-                submit: args.opt_value_from_str("--submit")?,
-                dhat: args.contains("--dhat"),
-            },
+            Some("solve") => {
+                let dhat = args.contains("--dhat");
+                let checked = args.contains("--checked");
+                // both select a cargo profile, so only one of them can apply.
+                if dhat && checked {
+                    return Err("--dhat and --checked cannot be combined.".into());
+                }
+
+                AppArguments::Solve {
+                    day: args.free_from_str()?,
+                    release: args.contains("--release"),
+                    submit: args.opt_value_from_str("--submit")?,
+                    dhat,
+                    checked,
+                    viz: args.contains("--viz"),
+                    gif: args.opt_value_from_str("--gif")?,
+                    variant: args.opt_value_from_str("--variant")?,
+                }
+            }
             #[cfg(feature = "today")]
             Some("today") => AppArguments::Today,
             Some(x) => {
@@ -102,8 +156,19 @@ fn main() {
             std::process::exit(1);
         }
         Ok(args) => match args {
-            AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
+            AppArguments::All { release, checked } => all::handle(release, checked),
+            AppArguments::Time {
+                day,
+                all,
+                store,
+                memory,
+                variants,
+                options,
+                formats,
+            } => time::handle(day, all, store, memory, variants, &options, &formats),
+            AppArguments::Status => status::handle(),
+            AppArguments::Readme => readme::handle(),
+            AppArguments::Watch { day, release } => watch::handle(day, release),
             AppArguments::Download { day } => download::handle(day),
             AppArguments::Read { day } => read::handle(day),
             AppArguments::Scaffold {
@@ -120,8 +185,12 @@ fn main() {
                 day,
                 release,
                 dhat,
+                checked,
+                viz,
+                gif,
+                variant,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+            } => solve::handle(day, release, dhat, checked, viz, gif, variant, submit),
             #[cfg(feature = "today")]
             AppArguments::Today => {
                 match Day::today() {
@@ -0,0 +1,159 @@
+//! Memoisation helpers for recursive counting puzzles.
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A keyed cache that records how often values were reused.
+///
+/// [`Memo::get_or_compute`] passes a recursion handle to the compute function,
+/// so recursive closures can look up sub-problems through the same cache.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    hits: usize,
+    misses: usize,
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self {
+            cache: HashMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Memo<K, V> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the cached value for `key`, computing it with `compute` on a miss.
+    /// `compute` receives a handle that resolves other keys through this cache.
+    pub fn get_or_compute<F>(&mut self, key: K, compute: &F) -> V
+    where
+        F: Fn(&mut dyn FnMut(K) -> V, &K) -> V,
+    {
+        if let Some(value) = self.cache.get(&key) {
+            self.hits += 1;
+            return value.clone();
+        }
+
+        self.misses += 1;
+        let value = compute(&mut |k| self.get_or_compute(k, compute), &key);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    #[must_use]
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    /// Number of lookups that were answered from the cache.
+    #[must_use]
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Number of lookups that had to be computed.
+    #[must_use]
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+}
+
+/// A multiset that maps each distinct item to how often it occurs.
+pub type Counts<T> = HashMap<T, usize>;
+
+/// Collects items into a [`Counts`] map.
+pub fn count<T: Hash + Eq>(items: impl IntoIterator<Item = T>) -> Counts<T> {
+    let mut counts = Counts::new();
+    for item in items {
+        *counts.entry(item).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Advances every item by one step, where each item turns into the items returned by `step`.
+/// Equal items are only expanded once, so the work depends on the number of distinct items.
+pub fn evolve<T, I>(counts: &Counts<T>, mut step: impl FnMut(&T) -> I) -> Counts<T>
+where
+    T: Hash + Eq,
+    I: IntoIterator<Item = T>,
+{
+    let mut next = Counts::with_capacity(counts.len());
+    for (item, count) in counts {
+        for successor in step(item) {
+            *next.entry(successor).or_insert(0) += count;
+        }
+    }
+    next
+}
+
+/// Applies [`evolve`] `steps` times.
+pub fn evolve_n<T, I>(
+    mut counts: Counts<T>,
+    steps: usize,
+    mut step: impl FnMut(&T) -> I,
+) -> Counts<T>
+where
+    T: Hash + Eq,
+    I: IntoIterator<Item = T>,
+{
+    for _ in 0..steps {
+        counts = evolve(&counts, &mut step);
+    }
+    counts
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{count, evolve_n, Memo};
+
+    #[test]
+    fn memoises_recursive_closures() {
+        let mut memo = Memo::new();
+        let fib = memo.get_or_compute(90_u64, &|fib, &n| {
+            if n < 2 {
+                n
+            } else {
+                fib(n - 1) + fib(n - 2)
+            }
+        });
+
+        assert_eq!(fib, 2_880_067_194_370_816_120);
+        assert_eq!(memo.misses(), 91);
+        assert_eq!(memo.hits(), 88);
+        assert_eq!(memo.get(&10), Some(&55));
+    }
+
+    #[test]
+    fn evolves_counts() {
+        // every item splits into two smaller items, `0` disappears.
+        let counts = evolve_n(count([3_u32, 3]), 2, |&n| match n {
+            0 => vec![],
+            n => vec![n - 1, n - 1],
+        });
+
+        assert_eq!(counts.get(&1), Some(&8));
+        assert_eq!(counts.values().sum::<usize>(), 8);
+    }
+}
@@ -0,0 +1,176 @@
+//! Checks that a sequence keeps increasing or keeps decreasing in steps of bounded size, optionally after
+//! removing up to `k` of its elements.
+//!
+//! Every element is only compared with the `k + 1` elements before it, so a check takes `O(n * k²)` time,
+//! linear in the length of the sequence for a small number of removals.
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Increasing,
+    Decreasing,
+}
+
+/// How to make a sequence monotonic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repair {
+    pub trend: Trend,
+    /// Indices of the elements to remove, in ascending order.
+    pub removed: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MonotonicChecker {
+    steps: RangeInclusive<i64>,
+    max_removals: usize,
+}
+
+impl MonotonicChecker {
+    /// Accepts sequences where every step between neighbours has a size within `steps`, all in the same
+    /// direction.
+    #[must_use]
+    pub fn new(steps: RangeInclusive<i64>) -> Self {
+        Self {
+            steps,
+            max_removals: 0,
+        }
+    }
+
+    /// Allows removing up to `max_removals` elements to satisfy the rule.
+    #[must_use]
+    pub fn with_removals(mut self, max_removals: usize) -> Self {
+        self.max_removals = max_removals;
+        self
+    }
+
+    #[must_use]
+    pub fn is_valid(&self, values: &[i64]) -> bool {
+        self.repair(values).is_some()
+    }
+
+    /// The fewest removals that make `values` monotonic, `None` if more than the allowed removals are
+    /// needed. Prefers an increasing sequence if both directions need the same number of removals.
+    #[must_use]
+    pub fn repair(&self, values: &[i64]) -> Option<Repair> {
+        let increasing = self
+            .removals(values, |a, b| self.steps.contains(&(b - a)))
+            .map(|removed| Repair {
+                trend: Trend::Increasing,
+                removed,
+            });
+        let decreasing = self
+            .removals(values, |a, b| self.steps.contains(&(a - b)))
+            .map(|removed| Repair {
+                trend: Trend::Decreasing,
+                removed,
+            });
+        match (increasing, decreasing) {
+            (Some(up), Some(down)) if down.removed.len() < up.removed.len() => Some(down),
+            (Some(up), _) => Some(up),
+            (None, down) => down,
+        }
+    }
+
+    /// The fewest removals so that `fits` holds for all neighbours that are kept.
+    fn removals(&self, values: &[i64], fits: impl Fn(i64, i64) -> bool) -> Option<Vec<usize>> {
+        let n = values.len();
+        if n == 0 {
+            return Some(vec![]);
+        }
+        let k = self.max_removals;
+
+        // `previous[i][r]` is set if `values[i]` can be kept after `r` removals before it, and holds the
+        // kept element before it, `Some(None)` if `i` is the first kept element.
+        let mut previous: Vec<Vec<Option<Option<usize>>>> = vec![vec![None; k + 1]; n];
+        for i in 0..n {
+            if i <= k {
+                previous[i][i] = Some(None);
+            }
+            for j in i.saturating_sub(k + 1)..i {
+                if !fits(values[j], values[i]) {
+                    continue;
+                }
+                let skipped = i - j - 1;
+                for r in skipped..=k {
+                    if previous[i][r].is_none() && previous[j][r - skipped].is_some() {
+                        previous[i][r] = Some(Some(j));
+                    }
+                }
+            }
+        }
+
+        // the elements after the last kept one are removed as well.
+        let (mut i, mut r) = (0..n)
+            .flat_map(|i| (0..=k).map(move |r| (i, r)))
+            .filter(|&(i, r)| previous[i][r].is_some() && r + (n - 1 - i) <= k)
+            .min_by_key(|&(i, r)| r + (n - 1 - i))?;
+
+        let mut kept = vec![false; n];
+        loop {
+            kept[i] = true;
+            match previous[i][r] {
+                Some(Some(j)) => {
+                    r -= i - j - 1;
+                    i = j;
+                }
+                _ => break,
+            }
+        }
+        Some((0..n).filter(|&index| !kept[index]).collect())
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{MonotonicChecker, Repair, Trend};
+
+    #[test]
+    fn checks_steps_and_direction() {
+        let checker = MonotonicChecker::new(1..=3);
+        assert!(checker.is_valid(&[7, 6, 4, 2, 1]));
+        assert!(checker.is_valid(&[1, 3, 6, 7, 9]));
+        assert!(!checker.is_valid(&[1, 2, 7, 8, 9]));
+        assert!(!checker.is_valid(&[8, 6, 4, 4, 1]));
+        assert!(!checker.is_valid(&[1, 3, 2, 4, 5]));
+        assert!(checker.is_valid(&[5]));
+        assert!(checker.is_valid(&[]));
+
+        let wide = MonotonicChecker::new(0..=10);
+        assert!(wide.is_valid(&[8, 6, 4, 4, 1]));
+    }
+
+    #[test]
+    fn reports_removals() {
+        let checker = MonotonicChecker::new(1..=3).with_removals(1);
+        assert_eq!(
+            checker.repair(&[1, 3, 2, 4, 5]),
+            Some(Repair {
+                trend: Trend::Increasing,
+                removed: vec![2],
+            })
+        );
+        assert_eq!(
+            checker.repair(&[8, 6, 4, 4, 1]),
+            Some(Repair {
+                trend: Trend::Decreasing,
+                removed: vec![3],
+            })
+        );
+        assert_eq!(checker.repair(&[1, 2, 7, 8, 9]), None);
+        // the first and the last element can be removed as well.
+        assert_eq!(checker.repair(&[9, 1, 2, 3]).unwrap().removed, [0]);
+        assert_eq!(checker.repair(&[1, 2, 3, 9]).unwrap().removed, [3]);
+        assert_eq!(checker.repair(&[1, 2, 3]).unwrap().removed, []);
+    }
+
+    #[test]
+    fn allows_several_removals() {
+        let values = [1, 9, 2, 8, 3, 4];
+        let checker = MonotonicChecker::new(1..=3);
+        assert!(!checker.clone().with_removals(1).is_valid(&values));
+
+        let repair = checker.with_removals(2).repair(&values).unwrap();
+        assert_eq!(repair.trend, Trend::Increasing);
+        assert_eq!(repair.removed, [1, 3]);
+    }
+}
@@ -0,0 +1,258 @@
+//! Pattern matching on character grids: words along straight rays and small two-dimensional stencils.
+//!
+//! [`WordSearch`] follows every ray from every cell through a trie of the words, so all words are found
+//! in a single pass without building candidate strings. [`Stencil`] matches a mask with wildcards in all of
+//! its distinct rotations and reflections.
+use crate::grid::{Grid, Pos};
+
+/// The eight directions words can run in, clockwise from up, as `(dx, dy)` offsets.
+pub const RAYS: [(isize, isize); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+/// A word found in the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WordMatch {
+    /// Index of the word in the list passed to [`WordSearch::new`].
+    pub word: usize,
+    /// Position of the first character.
+    pub start: Pos,
+    /// The direction the word runs in, one of [`RAYS`].
+    pub direction: (isize, isize),
+}
+
+#[derive(Debug, Clone)]
+pub struct WordSearch {
+    /// Trie nodes, node `0` is the root.
+    children: Vec<Vec<(char, usize)>>,
+    /// The word ending at each node.
+    word: Vec<Option<usize>>,
+}
+
+impl WordSearch {
+    #[must_use]
+    pub fn new(words: &[&str]) -> Self {
+        let mut search = Self {
+            children: vec![vec![]],
+            word: vec![None],
+        };
+        for (index, word) in words.iter().enumerate() {
+            let mut node = 0;
+            for c in word.chars() {
+                node = search.child(node, c).unwrap_or_else(|| {
+                    let child = search.children.len();
+                    search.children.push(vec![]);
+                    search.word.push(None);
+                    search.children[node].push((c, child));
+                    child
+                });
+            }
+            search.word[node] = Some(index);
+        }
+        search
+    }
+
+    fn child(&self, node: usize, c: char) -> Option<usize> {
+        self.children[node]
+            .iter()
+            .find(|&&(edge, _)| edge == c)
+            .map(|&(_, child)| child)
+    }
+
+    /// Every occurrence of every word, in all eight directions.
+    ///
+    /// A palindrome is found twice at the same start, once in each direction.
+    #[must_use]
+    pub fn find_all(&self, grid: &Grid<char>) -> Vec<WordMatch> {
+        let mut matches = vec![];
+        for start in grid.positions() {
+            for direction in RAYS {
+                let (mut pos, mut node) = (Some(start), 0);
+                while let Some(next) = pos.and_then(|p| self.child(node, grid[p])) {
+                    node = next;
+                    if let Some(word) = self.word[node] {
+                        matches.push(WordMatch {
+                            word,
+                            start,
+                            direction,
+                        });
+                    }
+                    pos = pos.and_then(|p| grid.offset(p, direction));
+                }
+            }
+        }
+        matches
+    }
+}
+
+/// One of the eight symmetries of a square: an optional mirroring along the vertical axis, followed by
+/// clockwise quarter turns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Orientation {
+    pub reflected: bool,
+    pub quarter_turns: u8,
+}
+
+impl Orientation {
+    /// All symmetries, starting with the identity.
+    #[must_use]
+    pub fn all() -> [Orientation; 8] {
+        std::array::from_fn(|i| Orientation {
+            reflected: i >= 4,
+            quarter_turns: (i % 4) as u8,
+        })
+    }
+}
+
+/// A stencil match, `pos` is the top-left corner of the oriented stencil.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StencilMatch {
+    pub pos: Pos,
+    pub orientation: Orientation,
+}
+
+/// A small rectangular mask, every cell has to match except for wildcards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stencil {
+    width: usize,
+    height: usize,
+    /// Positions and characters of the cells that are not wildcards, sorted by position.
+    cells: Vec<(Pos, char)>,
+}
+
+impl Stencil {
+    /// Parses one row per line, `wildcard` matches any character.
+    #[must_use]
+    pub fn parse(pattern: &str, wildcard: char) -> Self {
+        let grid = Grid::from_chars(pattern);
+        Self {
+            width: grid.width,
+            height: grid.height,
+            cells: grid
+                .iter()
+                .filter(|&(_, &c)| c != wildcard)
+                .map(|(pos, &c)| (pos, c))
+                .collect(),
+        }
+    }
+
+    /// The stencil turned into `orientation`.
+    #[must_use]
+    pub fn oriented(&self, orientation: Orientation) -> Self {
+        let mut stencil = self.clone();
+        if orientation.reflected {
+            for ((x, _), _) in &mut stencil.cells {
+                *x = stencil.width - 1 - *x;
+            }
+        }
+        for _ in 0..orientation.quarter_turns {
+            // a clockwise turn moves the left column to the top row.
+            for ((x, y), _) in &mut stencil.cells {
+                (*x, *y) = (stencil.height - 1 - *y, *x);
+            }
+            (stencil.width, stencil.height) = (stencil.height, stencil.width);
+        }
+        stencil.cells.sort_unstable_by_key(|&((x, y), _)| (y, x));
+        stencil
+    }
+
+    /// The distinct orientations of the stencil, symmetric stencils have fewer than eight.
+    #[must_use]
+    pub fn orientations(&self) -> Vec<(Orientation, Stencil)> {
+        let mut orientations: Vec<(Orientation, Stencil)> = vec![];
+        for orientation in Orientation::all() {
+            let stencil = self.oriented(orientation);
+            if orientations.iter().all(|(_, other)| *other != stencil) {
+                orientations.push((orientation, stencil));
+            }
+        }
+        orientations
+    }
+
+    /// Whether the stencil matches with its top-left corner at `pos`, as it is oriented.
+    #[must_use]
+    pub fn matches_at(&self, grid: &Grid<char>, (x, y): Pos) -> bool {
+        x + self.width <= grid.width
+            && y + self.height <= grid.height
+            && self
+                .cells
+                .iter()
+                .all(|&((dx, dy), c)| grid[(x + dx, y + dy)] == c)
+    }
+
+    /// Every match in any of the distinct orientations.
+    #[must_use]
+    pub fn find_all(&self, grid: &Grid<char>) -> Vec<StencilMatch> {
+        let orientations = self.orientations();
+        grid.positions()
+            .flat_map(|pos| {
+                orientations
+                    .iter()
+                    .filter(move |(_, stencil)| stencil.matches_at(grid, pos))
+                    .map(move |&(orientation, _)| StencilMatch { pos, orientation })
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Orientation, Stencil, WordMatch, WordSearch};
+    use crate::grid::Grid;
+
+    #[test]
+    fn finds_words_in_all_directions() {
+        let grid = Grid::from_chars("CAT\nAXA\nTAC\n");
+        let search = WordSearch::new(&["CAT", "AX", "C"]);
+        let matches = search.find_all(&grid);
+
+        let cats: Vec<_> = matches.iter().filter(|m| m.word == 0).collect();
+        assert_eq!(cats.len(), 4);
+        assert!(matches.contains(&WordMatch {
+            word: 0,
+            start: (2, 2),
+            direction: (0, -1),
+        }));
+        assert_eq!(matches.iter().filter(|m| m.word == 1).count(), 4);
+        // single characters match once per direction.
+        assert_eq!(matches.iter().filter(|m| m.word == 2).count(), 16);
+    }
+
+    #[test]
+    fn orients_stencils() {
+        let corner = Stencil::parse("AB\nC.\n", '.');
+        let turned = corner.oriented(Orientation {
+            reflected: false,
+            quarter_turns: 1,
+        });
+        assert_eq!(turned, Stencil::parse("CA\n.B\n", '.'));
+        let mirrored = corner.oriented(Orientation {
+            reflected: true,
+            quarter_turns: 0,
+        });
+        assert_eq!(mirrored, Stencil::parse("BA\n.C\n", '.'));
+
+        assert_eq!(corner.orientations().len(), 8);
+        assert_eq!(
+            Stencil::parse("M.S\n.A.\nM.S\n", '.').orientations().len(),
+            4
+        );
+        assert_eq!(Stencil::parse("#\n", '.').orientations().len(), 1);
+    }
+
+    #[test]
+    fn matches_stencils() {
+        let grid = Grid::from_chars("M.M\n.A.\nS.S\n..M\n");
+        let matches = Stencil::parse("M.S\n.A.\nM.S\n", '.').find_all(&grid);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].pos, (0, 0));
+        assert_eq!(matches[0].orientation.quarter_turns % 2, 1);
+    }
+}
@@ -0,0 +1,305 @@
+//! A sokoban-like engine for pushing objects with arbitrary footprints around a walled grid.
+//!
+//! A push is transactional: all objects that would be moved are collected and checked against walls
+//! first, and only if none of them is blocked are they moved together. Every push is recorded, so it can
+//! be undone again, and a [`Replay`] steps through a list of moves in both directions.
+use crate::grid::{Direction, Grid, Pos};
+
+pub type ObjectId = usize;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Object<K> {
+    pub kind: K,
+    pub origin: Pos,
+    /// Offsets of the occupied cells relative to `origin`.
+    pub footprint: Vec<Pos>,
+}
+
+impl<K> Object<K> {
+    /// The cells the object currently occupies.
+    pub fn cells(&self) -> impl Iterator<Item = Pos> + '_ {
+        self.footprint
+            .iter()
+            .map(|&(dx, dy)| (self.origin.0 + dx, self.origin.1 + dy))
+    }
+}
+
+/// A recorded push, the objects are empty if the push was blocked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Push {
+    pub direction: Direction,
+    pub moved: Vec<ObjectId>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PushWorld<K> {
+    walls: Grid<bool>,
+    occupied: Grid<Option<ObjectId>>,
+    objects: Vec<Object<K>>,
+    history: Vec<Push>,
+}
+
+impl<K> PushWorld<K> {
+    /// Creates an empty world, `walls` marks the cells objects can never enter.
+    #[must_use]
+    pub fn new(walls: Grid<bool>) -> Self {
+        let occupied = walls.map(|_, _| None);
+        Self {
+            walls,
+            occupied,
+            objects: vec![],
+            history: vec![],
+        }
+    }
+
+    /// Places an object, `footprint` lists its cells relative to `origin`.
+    ///
+    /// # Panics
+    /// If a cell of the object is outside the grid, a wall or already occupied.
+    pub fn add(&mut self, kind: K, origin: Pos, footprint: Vec<Pos>) -> ObjectId {
+        let id = self.objects.len();
+        let object = Object {
+            kind,
+            origin,
+            footprint,
+        };
+        for cell in object.cells() {
+            assert!(self.is_free(cell), "{cell:?} is not free");
+            self.occupied[cell] = Some(id);
+        }
+        self.objects.push(object);
+        id
+    }
+
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.walls.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.walls.height
+    }
+
+    #[must_use]
+    pub fn is_wall(&self, pos: Pos) -> bool {
+        self.walls.get(pos).copied().unwrap_or(true)
+    }
+
+    fn is_free(&self, pos: Pos) -> bool {
+        !self.is_wall(pos) && self.occupied[pos].is_none()
+    }
+
+    #[must_use]
+    pub fn object(&self, id: ObjectId) -> &Object<K> {
+        &self.objects[id]
+    }
+
+    pub fn objects(&self) -> impl Iterator<Item = (ObjectId, &Object<K>)> {
+        self.objects.iter().enumerate()
+    }
+
+    /// The object occupying `pos`.
+    #[must_use]
+    pub fn object_at(&self, pos: Pos) -> Option<ObjectId> {
+        self.occupied.get(pos).copied().flatten()
+    }
+
+    /// Every push performed so far, oldest first.
+    #[must_use]
+    pub fn history(&self) -> &[Push] {
+        &self.history
+    }
+
+    /// Collects the objects that pushing `id` in `direction` would move, including `id` itself.
+    /// Returns `None` if any of them would run into a wall.
+    #[must_use]
+    pub fn affected(&self, id: ObjectId, direction: Direction) -> Option<Vec<ObjectId>> {
+        let mut affected = vec![id];
+        let mut seen = vec![false; self.objects.len()];
+        seen[id] = true;
+
+        let mut i = 0;
+        while let Some(&current) = affected.get(i) {
+            for cell in self.objects[current].cells() {
+                let next = self.walls.step(cell, direction)?;
+                if self.walls[next] {
+                    return None;
+                }
+                if let Some(other) = self.occupied[next] {
+                    if !seen[other] {
+                        seen[other] = true;
+                        affected.push(other);
+                    }
+                }
+            }
+            i += 1;
+        }
+        Some(affected)
+    }
+
+    fn shift(&mut self, ids: &[ObjectId], direction: Direction) {
+        for &id in ids {
+            for cell in self.objects[id].cells().collect::<Vec<_>>() {
+                self.occupied[cell] = None;
+            }
+        }
+        for &id in ids {
+            let origin = self.objects[id].origin;
+            // footprints start at the origin, so the origin stays inside the grid as well.
+            let (dx, dy) = direction.offset();
+            self.objects[id].origin = (
+                origin.0.wrapping_add_signed(dx),
+                origin.1.wrapping_add_signed(dy),
+            );
+            for cell in self.objects[id].cells().collect::<Vec<_>>() {
+                self.occupied[cell] = Some(id);
+            }
+        }
+    }
+
+    /// Pushes object `id` one step in `direction`, moving everything in its way.
+    /// Returns `false` and leaves the world unchanged if the push is blocked.
+    pub fn push(&mut self, id: ObjectId, direction: Direction) -> bool {
+        let moved = self.affected(id, direction).unwrap_or_default();
+        self.shift(&moved, direction);
+        let pushed = !moved.is_empty();
+        self.history.push(Push { direction, moved });
+        pushed
+    }
+
+    /// Reverts the most recent push. Returns `false` if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(push) = self.history.pop() else {
+            return false;
+        };
+        self.shift(&push.moved, push.direction.opposite());
+        true
+    }
+}
+
+/// Steps a pusher through a list of moves, forwards and backwards.
+#[derive(Debug, Clone)]
+pub struct Replay<K> {
+    pub world: PushWorld<K>,
+    pusher: ObjectId,
+    moves: Vec<Direction>,
+    next: usize,
+}
+
+impl<K> Replay<K> {
+    #[must_use]
+    pub fn new(world: PushWorld<K>, pusher: ObjectId, moves: Vec<Direction>) -> Self {
+        Self {
+            world,
+            pusher,
+            moves,
+            next: 0,
+        }
+    }
+
+    /// Number of moves performed so far.
+    #[must_use]
+    pub fn position(&self) -> usize {
+        self.next
+    }
+
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.next == self.moves.len()
+    }
+
+    /// Performs the next move. Returns whether anything moved, or `None` once all moves are done.
+    pub fn step(&mut self) -> Option<bool> {
+        let &direction = self.moves.get(self.next)?;
+        self.next += 1;
+        Some(self.world.push(self.pusher, direction))
+    }
+
+    /// Reverts the previous move. Returns `false` at the start of the move list.
+    pub fn step_back(&mut self) -> bool {
+        if self.next == 0 {
+            return false;
+        }
+        self.next -= 1;
+        self.world.undo()
+    }
+
+    /// Performs all remaining moves.
+    pub fn run(&mut self) {
+        while self.step().is_some() {}
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{PushWorld, Replay};
+    use crate::grid::{Direction, Grid};
+
+    /// A 6x4 room with walls around it.
+    fn room() -> PushWorld<char> {
+        let walls = Grid::from_fn(6, 4, |(x, y)| x == 0 || y == 0 || x == 5 || y == 3);
+        PushWorld::new(walls)
+    }
+
+    #[test]
+    fn pushes_chains_of_objects() {
+        let mut world = room();
+        let robot = world.add('@', (1, 1), vec![(0, 0)]);
+        let first = world.add('O', (2, 1), vec![(0, 0)]);
+        let second = world.add('O', (3, 1), vec![(0, 0)]);
+
+        assert!(world.push(robot, Direction::Right));
+        assert_eq!(world.object(first).origin, (3, 1));
+        assert_eq!(world.object(second).origin, (4, 1));
+
+        // the chain is against the wall now, nothing moves.
+        assert!(!world.push(robot, Direction::Right));
+        assert_eq!(world.object(robot).origin, (2, 1));
+        assert_eq!(world.object_at((1, 1)), None);
+    }
+
+    #[test]
+    fn checks_wide_objects_transactionally() {
+        let mut world = room();
+        let robot = world.add('@', (2, 2), vec![(0, 0)]);
+        let wide = world.add('[', (2, 1), vec![(0, 0), (1, 0)]);
+        assert!(!world.push(robot, Direction::Up));
+
+        // pushing sideways moves both halves, and the robot can follow through the gap.
+        assert!(world.push(wide, Direction::Right));
+        assert_eq!(
+            world.object(wide).cells().collect::<Vec<_>>(),
+            [(3, 1), (4, 1)]
+        );
+        assert!(!world.push(wide, Direction::Right));
+        assert_eq!(world.object_at((2, 1)), None);
+    }
+
+    #[test]
+    fn replays_and_undoes_moves() {
+        let mut world = room();
+        let robot = world.add('@', (1, 2), vec![(0, 0)]);
+        let crate_ = world.add('O', (2, 2), vec![(0, 0)]);
+        let moves = vec![
+            Direction::Right,
+            Direction::Right,
+            Direction::Right,
+            Direction::Up,
+        ];
+        let mut replay = Replay::new(world, robot, moves);
+
+        assert_eq!(replay.step(), Some(true));
+        assert_eq!(replay.step(), Some(true));
+        assert_eq!(replay.step(), Some(false));
+        replay.run();
+        assert!(replay.is_finished());
+        assert_eq!(replay.world.object(robot).origin, (3, 1));
+
+        while replay.step_back() {}
+        assert_eq!(replay.position(), 0);
+        assert_eq!(replay.world.object(robot).origin, (1, 2));
+        assert_eq!(replay.world.object(crate_).origin, (2, 2));
+        assert!(replay.world.history().is_empty());
+    }
+}
@@ -0,0 +1,225 @@
+//! Connected components of a [`Grid`]: region labelling, area, perimeter and side count.
+use std::fmt::Display;
+use std::io::{self, Write};
+
+use crate::grid::{Direction, Grid, Pos};
+
+/// A labelling of a grid into orthogonally connected regions of equal cells.
+#[derive(Debug, Clone)]
+pub struct Regions {
+    labels: Grid<usize>,
+    regions: Vec<Vec<Pos>>,
+}
+
+impl Regions {
+    /// Labels regions of equal neighbouring cells.
+    pub fn new<T: PartialEq>(grid: &Grid<T>) -> Self {
+        Self::new_by(grid, |a, b| a == b)
+    }
+
+    /// Labels regions where neighbouring cells belong together if `connected` returns `true` for them.
+    pub fn new_by<T>(grid: &Grid<T>, connected: impl Fn(&T, &T) -> bool) -> Self {
+        let mut labels = Grid::new(grid.width, grid.height, usize::MAX);
+        let mut regions = vec![];
+
+        for start in grid.positions() {
+            if labels[start] != usize::MAX {
+                continue;
+            }
+
+            let id = regions.len();
+            labels[start] = id;
+            let mut region = vec![start];
+            let mut stack = vec![start];
+            while let Some(pos) = stack.pop() {
+                for next in grid.neighbours(pos) {
+                    if labels[next] == usize::MAX && connected(&grid[pos], &grid[next]) {
+                        labels[next] = id;
+                        region.push(next);
+                        stack.push(next);
+                    }
+                }
+            }
+            regions.push(region);
+        }
+
+        Self { labels, regions }
+    }
+
+    /// Number of regions.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    /// Id of the region containing `pos`.
+    #[must_use]
+    pub fn label(&self, pos: Pos) -> usize {
+        self.labels[pos]
+    }
+
+    /// Positions of the cells in region `id`.
+    #[must_use]
+    pub fn cells(&self, id: usize) -> &[Pos] {
+        &self.regions[id]
+    }
+
+    /// Region ids, from the top-left region onwards.
+    pub fn ids(&self) -> impl Iterator<Item = usize> {
+        0..self.regions.len()
+    }
+
+    /// Id of the region at `pos` moved one step in `direction`, if that is inside the grid.
+    fn neighbour_label(&self, pos: Pos, direction: Direction) -> Option<usize> {
+        self.labels
+            .step(pos, direction)
+            .map(|next| self.labels[next])
+    }
+
+    fn is_boundary(&self, pos: Pos, direction: Direction) -> bool {
+        self.neighbour_label(pos, direction) != Some(self.labels[pos])
+    }
+
+    #[must_use]
+    pub fn area(&self, id: usize) -> usize {
+        self.regions[id].len()
+    }
+
+    /// Number of cell edges between the region and other regions or the outside of the grid.
+    #[must_use]
+    pub fn perimeter(&self, id: usize) -> usize {
+        self.regions[id]
+            .iter()
+            .map(|&pos| {
+                Direction::ALL
+                    .into_iter()
+                    .filter(|&d| self.is_boundary(pos, d))
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Number of straight sides of the region, which equals its number of corners.
+    #[must_use]
+    pub fn sides(&self, id: usize) -> usize {
+        self.regions[id]
+            .iter()
+            .map(|&pos| {
+                Direction::ALL
+                    .into_iter()
+                    .filter(|&d| self.is_corner(pos, d, d.turn_right()))
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Whether the cell at `pos` has a convex or concave corner between the two orthogonal directions.
+    fn is_corner(&self, pos: Pos, a: Direction, b: Direction) -> bool {
+        match (self.is_boundary(pos, a), self.is_boundary(pos, b)) {
+            (true, true) => true,
+            (false, false) => {
+                // both neighbours belong to the region, so there is an inner corner if the diagonal does not.
+                let diagonal = self
+                    .labels
+                    .step(pos, a)
+                    .and_then(|next| self.neighbour_label(next, b));
+                diagonal != Some(self.labels[pos])
+            }
+            _ => false,
+        }
+    }
+
+    /// Writes the grid with region boundaries drawn between cells.
+    ///
+    /// Every cell takes the odd positions of a `(2 * width + 1) x (2 * height + 1)` character canvas,
+    /// boundaries are drawn as `-` and `|` and joined with `+`.
+    pub fn render_boundaries<T: Display>(
+        &self,
+        grid: &Grid<T>,
+        writer: &mut impl Write,
+    ) -> io::Result<()> {
+        let (width, height) = (grid.width * 2 + 1, grid.height * 2 + 1);
+        let mut canvas = Grid::new(width, height, ' ');
+
+        for (pos @ (x, y), cell) in grid.iter() {
+            let (cx, cy) = (x * 2 + 1, y * 2 + 1);
+            canvas[(cx, cy)] = cell.to_string().chars().next().unwrap_or(' ');
+            for direction in Direction::ALL {
+                if !self.is_boundary(pos, direction) {
+                    continue;
+                }
+                match direction {
+                    Direction::Up => canvas[(cx, cy - 1)] = '-',
+                    Direction::Down => canvas[(cx, cy + 1)] = '-',
+                    Direction::Left => canvas[(cx - 1, cy)] = '|',
+                    Direction::Right => canvas[(cx + 1, cy)] = '|',
+                }
+            }
+        }
+
+        // corners sit on even coordinates and join any boundary next to them.
+        for y in (0..height).step_by(2) {
+            for x in (0..width).step_by(2) {
+                if canvas.neighbours((x, y)).any(|p| canvas[p] != ' ') {
+                    canvas[(x, y)] = '+';
+                }
+            }
+        }
+
+        writeln!(writer, "{}", canvas.render(|_, &c| c))
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Regions;
+    use crate::grid::Grid;
+
+    const GARDEN: &str = "AAAA\nBBCD\nBBCC\nEEEC\n";
+
+    #[test]
+    fn labels_regions() {
+        let grid = Grid::from_chars(GARDEN);
+        let regions = Regions::new(&grid);
+
+        assert_eq!(regions.len(), 5);
+        assert_eq!(regions.label((0, 1)), regions.label((1, 2)));
+        assert_ne!(regions.label((2, 1)), regions.label((3, 1)));
+
+        let areas: Vec<_> = regions.ids().map(|id| regions.area(id)).collect();
+        assert_eq!(areas, [4, 4, 4, 1, 3]);
+    }
+
+    #[test]
+    fn measures_perimeter_and_sides() {
+        let grid = Grid::from_chars(GARDEN);
+        let regions = Regions::new(&grid);
+
+        let perimeters: Vec<_> = regions.ids().map(|id| regions.perimeter(id)).collect();
+        assert_eq!(perimeters, [10, 8, 10, 4, 8]);
+        let sides: Vec<_> = regions.ids().map(|id| regions.sides(id)).collect();
+        assert_eq!(sides, [4, 4, 8, 4, 4]);
+
+        // a region enclosing another one also counts its inner sides.
+        let enclosed = Regions::new(&Grid::from_chars("OOO\nOXO\nOOO\n"));
+        assert_eq!(enclosed.sides(enclosed.label((0, 0))), 8);
+        assert_eq!(enclosed.perimeter(enclosed.label((0, 0))), 16);
+    }
+
+    #[test]
+    fn renders_boundaries() {
+        let grid = Grid::from_chars("AB\nAA\n");
+        let mut output = vec![];
+        Regions::new(&grid)
+            .render_boundaries(&grid, &mut output)
+            .unwrap();
+
+        let expected = "+-+-+\n|A|B|\n+ +-+\n|A A|\n+-+-+\n";
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+}
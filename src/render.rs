@@ -0,0 +1,249 @@
+//! Renders grid simulations as animated GIFs.
+//!
+//! Days opt in by creating a [`GifRenderer`] via [`GifRenderer::from_args`] and pushing a frame for every
+//! simulation step. Recording is enabled with `cargo solve DD --gif out.gif` and skipped during `--time` runs.
+use std::{
+    env,
+    fs::File,
+    io::{self, BufWriter},
+    path::PathBuf,
+};
+
+use gif::{Encoder, EncodingError, Frame, Repeat};
+
+/// Black, white and a handful of distinguishable colors.
+pub const DEFAULT_PALETTE: &[[u8; 3]] = &[
+    [0x0F, 0x0F, 0x23],
+    [0xCC, 0xCC, 0xCC],
+    [0xFF, 0xFF, 0x66],
+    [0x00, 0x99, 0x00],
+    [0xE0, 0x40, 0x40],
+    [0x40, 0x80, 0xFF],
+    [0xFF, 0x99, 0x00],
+    [0x99, 0x44, 0xCC],
+];
+
+#[derive(Debug, Clone)]
+pub struct GifOptions {
+    pub path: PathBuf,
+    /// Width and height of a single grid cell in pixels.
+    pub scale: usize,
+    /// Only every n-th pushed frame is written, see [`GifRenderer::push`].
+    pub frame_skip: usize,
+    /// Delay between frames in hundredths of a second.
+    pub delay: u16,
+    pub palette: Vec<[u8; 3]>,
+}
+
+impl GifOptions {
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            scale: 4,
+            frame_skip: 1,
+            delay: 5,
+            palette: DEFAULT_PALETTE.to_vec(),
+        }
+    }
+
+    /// Reads the output path from a `--gif <path>` argument. Returns `None` if the flag is missing
+    /// or the solution is benchmarked.
+    #[must_use]
+    pub fn from_args() -> Option<Self> {
+        Self::parse_args(&env::args().collect::<Vec<_>>())
+    }
+
+    fn parse_args(args: &[String]) -> Option<Self> {
+        if args.iter().any(|a| a == "--time") {
+            return None;
+        }
+
+        let index = args.iter().position(|a| a == "--gif")?;
+        args.get(index + 1).map(Self::new)
+    }
+
+    #[must_use]
+    pub fn scale(self, scale: usize) -> Self {
+        Self { scale, ..self }
+    }
+
+    #[must_use]
+    pub fn frame_skip(self, frame_skip: usize) -> Self {
+        Self {
+            frame_skip: frame_skip.max(1),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn delay(self, delay: u16) -> Self {
+        Self { delay, ..self }
+    }
+
+    #[must_use]
+    pub fn palette(self, palette: &[[u8; 3]]) -> Self {
+        Self {
+            palette: palette.to_vec(),
+            ..self
+        }
+    }
+
+    /// Creates the output file for a grid of `width` x `height` cells.
+    pub fn create(self, width: usize, height: usize) -> Result<GifRenderer, EncodingError> {
+        let too_large = || {
+            EncodingError::from(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "gif dimensions exceed 65535 pixels",
+            ))
+        };
+        let pixel_width = u16::try_from(width * self.scale).map_err(|_| too_large())?;
+        let pixel_height = u16::try_from(height * self.scale).map_err(|_| too_large())?;
+
+        let palette: Vec<u8> = self.palette.iter().flatten().copied().collect();
+        let file = BufWriter::new(File::create(&self.path)?);
+        let mut encoder = Encoder::new(file, pixel_width, pixel_height, &palette)?;
+        encoder.set_repeat(Repeat::Infinite)?;
+
+        Ok(GifRenderer {
+            encoder,
+            width,
+            height,
+            options: self,
+            pushed: 0,
+            written: 0,
+            failed: false,
+        })
+    }
+}
+
+/// Appends grid snapshots to an animated GIF. The file is finalized when the renderer is dropped.
+/// Like creating the file, writing frames never fails the solution: the first error is reported on
+/// stderr and later frames are dropped.
+pub struct GifRenderer {
+    encoder: Encoder<BufWriter<File>>,
+    width: usize,
+    height: usize,
+    options: GifOptions,
+    pushed: usize,
+    written: usize,
+    failed: bool,
+}
+
+impl GifRenderer {
+    /// Starts recording if `--gif <path>` was passed. `configure` can adjust the defaults for the day.
+    /// Errors are reported on stderr, so a failing recording never aborts the solution.
+    pub fn from_args(
+        width: usize,
+        height: usize,
+        configure: impl FnOnce(GifOptions) -> GifOptions,
+    ) -> Option<Self> {
+        let options = configure(GifOptions::from_args()?);
+        let path = options.path.clone();
+
+        match options.create(width, height) {
+            Ok(renderer) => Some(renderer),
+            Err(e) => {
+                eprintln!("Failed to create gif {}: {e}", path.display());
+                None
+            }
+        }
+    }
+
+    /// Pushes a snapshot, `cell` maps grid coordinates to a palette index.
+    /// Only every `frame_skip`-th snapshot is written.
+    pub fn push(&mut self, cell: impl Fn(usize, usize) -> u8) {
+        self.pushed += 1;
+        if (self.pushed - 1).is_multiple_of(self.options.frame_skip) {
+            self.push_frame(cell);
+        }
+    }
+
+    /// Writes a snapshot regardless of `frame_skip`, e.g. for the final state.
+    pub fn push_frame(&mut self, cell: impl Fn(usize, usize) -> u8) {
+        if self.failed {
+            return;
+        }
+        if let Err(e) = self.write_frame(cell) {
+            eprintln!(
+                "Failed to write gif {}, stopping the recording: {e}",
+                self.options.path.display()
+            );
+            self.failed = true;
+        }
+    }
+
+    fn write_frame(&mut self, cell: impl Fn(usize, usize) -> u8) -> Result<(), EncodingError> {
+        let scale = self.options.scale;
+        let pixel_width = self.width * scale;
+
+        let mut buffer = vec![0; pixel_width * self.height * scale];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let index = cell(x, y);
+                for dy in 0..scale {
+                    let row = (y * scale + dy) * pixel_width + x * scale;
+                    buffer[row..row + scale].fill(index);
+                }
+            }
+        }
+
+        let mut frame = Frame::from_indexed_pixels(
+            u16::try_from(pixel_width).unwrap(),
+            u16::try_from(self.height * scale).unwrap(),
+            buffer,
+            None,
+        );
+        frame.delay = self.options.delay;
+
+        self.encoder.write_frame(&frame)?;
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Number of frames written to the file so far.
+    #[must_use]
+    pub fn frames_written(&self) -> usize {
+        self.written
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::fs;
+
+    use super::GifOptions;
+
+    #[test]
+    fn reads_path_from_args() {
+        let args = |args: &[&str]| args.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        let options = GifOptions::parse_args(&args(&["14", "--gif", "out.gif"])).unwrap();
+        assert_eq!(options.path.to_str(), Some("out.gif"));
+        assert!(GifOptions::parse_args(&args(&["14", "--gif", "out.gif", "--time"])).is_none());
+        assert!(GifOptions::parse_args(&args(&["14", "--gif"])).is_none());
+    }
+
+    #[test]
+    fn writes_scaled_frames() {
+        let path = std::env::temp_dir().join("advent_of_code_render_test.gif");
+        let mut renderer = GifOptions::new(&path)
+            .scale(2)
+            .frame_skip(3)
+            .create(3, 2)
+            .unwrap();
+
+        for i in 0..7 {
+            renderer.push(|x, y| ((x + y + i) % 2) as u8);
+        }
+        renderer.push_frame(|_, _| 1);
+        assert_eq!(renderer.frames_written(), 4);
+        drop(renderer);
+
+        let bytes = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(&bytes[..6], b"GIF89a");
+        // logical screen size, little endian.
+        assert_eq!(&bytes[6..10], &[6, 0, 4, 0]);
+    }
+}
@@ -0,0 +1,164 @@
+//! Contiguous spans on a line, e.g. blocks on a disk, and an allocator for free ones.
+//!
+//! [`SpanAllocator`] keeps one min-heap of start positions per span size, so the earliest free span
+//! that fits a request is found by looking at the top of every large enough heap.
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+
+/// The positions `start..start + len`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Span {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl Span {
+    #[must_use]
+    pub fn new(start: usize, len: usize) -> Self {
+        Self { start, len }
+    }
+
+    /// The first position after the span.
+    #[must_use]
+    pub fn end(&self) -> usize {
+        self.start + self.len
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Sum of all positions in the span, without iterating over them.
+    #[must_use]
+    pub fn position_sum(&self) -> usize {
+        self.len * self.start + self.len * self.len.saturating_sub(1) / 2
+    }
+}
+
+/// Free spans, handed out first-fit by position.
+///
+/// Freed spans are not merged with adjacent free spans.
+#[derive(Debug, Clone, Default)]
+pub struct SpanAllocator {
+    by_size: BTreeMap<usize, BinaryHeap<Reverse<usize>>>,
+}
+
+impl SpanAllocator {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a free span, empty spans are ignored.
+    pub fn free(&mut self, span: Span) {
+        if !span.is_empty() {
+            self.by_size
+                .entry(span.len)
+                .or_default()
+                .push(Reverse(span.start));
+        }
+    }
+
+    /// Total number of free positions.
+    #[must_use]
+    pub fn free_len(&self) -> usize {
+        self.by_size
+            .iter()
+            .map(|(size, starts)| size * starts.len())
+            .sum()
+    }
+
+    /// All free spans ordered by position.
+    #[must_use]
+    pub fn free_spans(&self) -> Vec<Span> {
+        let mut spans: Vec<_> = self
+            .by_size
+            .iter()
+            .flat_map(|(&size, starts)| {
+                starts
+                    .iter()
+                    .map(move |&Reverse(start)| Span::new(start, size))
+            })
+            .collect();
+        spans.sort_unstable();
+        spans
+    }
+
+    /// The earliest free span of at least `min_len` that starts before `before`.
+    fn first_fit(&self, min_len: usize, before: usize) -> Option<Span> {
+        self.by_size
+            .range(min_len.max(1)..)
+            .filter_map(|(&size, starts)| {
+                starts.peek().map(|&Reverse(start)| Span::new(start, size))
+            })
+            .filter(|span| span.start < before)
+            .min()
+    }
+
+    /// Takes the first `len` positions of the free span `span`, returning the rest to the allocator.
+    fn take(&mut self, span: Span, len: usize) -> Span {
+        let starts = self.by_size.get_mut(&span.len).unwrap();
+        starts.pop();
+        if starts.is_empty() {
+            self.by_size.remove(&span.len);
+        }
+        self.free(Span::new(span.start + len, span.len - len));
+        Span::new(span.start, len)
+    }
+
+    /// Allocates `len` positions at the start of the earliest free span that is large enough and
+    /// starts before `before`.
+    pub fn allocate(&mut self, len: usize, before: usize) -> Option<Span> {
+        let span = self.first_fit(len, before)?;
+        Some(self.take(span, len))
+    }
+
+    /// Allocates up to `max_len` positions from the earliest free span that starts before `before`,
+    /// however small it is.
+    pub fn allocate_partial(&mut self, max_len: usize, before: usize) -> Option<Span> {
+        let span = self.first_fit(1, before)?;
+        Some(self.take(span, span.len.min(max_len)))
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Span, SpanAllocator};
+
+    #[test]
+    fn sums_positions() {
+        assert_eq!(Span::new(3, 4).position_sum(), 3 + 4 + 5 + 6);
+        assert_eq!(Span::new(7, 1).position_sum(), 7);
+        assert_eq!(Span::new(7, 0).position_sum(), 0);
+        assert_eq!(Span::new(2, 3).end(), 5);
+    }
+
+    #[test]
+    fn allocates_first_fit() {
+        let mut spans = SpanAllocator::new();
+        spans.free(Span::new(10, 2));
+        spans.free(Span::new(2, 1));
+        spans.free(Span::new(20, 5));
+        spans.free(Span::new(30, 0));
+        assert_eq!(spans.free_len(), 8);
+
+        assert_eq!(spans.allocate(2, usize::MAX), Some(Span::new(10, 2)));
+        assert_eq!(spans.allocate(3, 20), None);
+        assert_eq!(spans.allocate(3, 21), Some(Span::new(20, 3)));
+        assert_eq!(spans.free_spans(), [Span::new(2, 1), Span::new(23, 2)]);
+    }
+
+    #[test]
+    fn allocates_partial_spans() {
+        let mut spans = SpanAllocator::new();
+        spans.free(Span::new(1, 2));
+        spans.free(Span::new(5, 3));
+
+        assert_eq!(spans.allocate_partial(4, 10), Some(Span::new(1, 2)));
+        assert_eq!(spans.allocate_partial(1, 10), Some(Span::new(5, 1)));
+        assert_eq!(spans.allocate_partial(4, 6), None);
+        assert_eq!(spans.allocate_partial(4, 10), Some(Span::new(6, 2)));
+        assert!(spans.free_spans().is_empty());
+    }
+}
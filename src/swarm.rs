@@ -0,0 +1,269 @@
+//! Robots moving at constant velocities on a grid that wraps around at its edges.
+//!
+//! Positions are computed in closed form for any point in time, and repeat after the [`Swarm::period`].
+//! A [`Detector`] scores how interesting the arrangement in a frame looks, and [`Swarm::best_frame`]
+//! searches a bounded number of frames for the highest score.
+use std::collections::HashMap;
+
+use crate::grid::{Grid, Pos};
+use crate::lattice::{Bounds, Vector};
+use crate::linear::lcm;
+use crate::regions::Regions;
+use crate::viz::Visualize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Robot {
+    pub position: Pos,
+    pub velocity: Vector,
+}
+
+impl Robot {
+    /// The position after `time` steps.
+    #[must_use]
+    pub fn position_at(&self, bounds: Bounds, time: usize) -> Pos {
+        // the axes wrap independently, so only the time within each axis period matters.
+        let wrap = |start: usize, velocity: isize, size: usize| {
+            let offset = velocity * (time % size) as isize;
+            (start as isize + offset).rem_euclid(size as isize) as usize
+        };
+        (
+            wrap(self.position.0, self.velocity.0, bounds.width),
+            wrap(self.position.1, self.velocity.1, bounds.height),
+        )
+    }
+}
+
+/// Scores a frame, higher scores are more interesting.
+pub trait Detector {
+    fn score(&self, positions: &[Pos], bounds: Bounds) -> f64;
+}
+
+/// Prefers frames whose rows and columns are occupied unevenly, by the negated Shannon entropy of the
+/// column and row histograms.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Entropy;
+
+impl Detector for Entropy {
+    fn score(&self, positions: &[Pos], bounds: Bounds) -> f64 {
+        let entropy = |counts: Vec<usize>| -> f64 {
+            let total = positions.len() as f64;
+            counts
+                .into_iter()
+                .filter(|&count| count > 0)
+                .map(|count| {
+                    let p = count as f64 / total;
+                    -p * p.log2()
+                })
+                .sum()
+        };
+        let mut columns = vec![0; bounds.width];
+        let mut rows = vec![0; bounds.height];
+        for &(x, y) in positions {
+            columns[x] += 1;
+            rows[y] += 1;
+        }
+        -(entropy(columns) + entropy(rows))
+    }
+}
+
+/// Prefers frames with the robots close together, by the negated variance of their coordinates.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Variance;
+
+impl Detector for Variance {
+    fn score(&self, positions: &[Pos], _: Bounds) -> f64 {
+        let variance = |values: Vec<f64>| {
+            let mean = values.iter().sum::<f64>() / values.len() as f64;
+            values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64
+        };
+        let xs = positions.iter().map(|&(x, _)| x as f64).collect();
+        let ys = positions.iter().map(|&(_, y)| y as f64).collect();
+        -(variance(xs) + variance(ys))
+    }
+}
+
+/// Prefers frames with large shapes, by the number of cells in the largest orthogonally connected group
+/// of occupied cells.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LargestComponent;
+
+impl Detector for LargestComponent {
+    fn score(&self, positions: &[Pos], bounds: Bounds) -> f64 {
+        let mut occupied = Grid::new(bounds.width, bounds.height, false);
+        for &pos in positions {
+            occupied[pos] = true;
+        }
+        let regions = Regions::new_by(&occupied, |&a, &b| a && b);
+        regions
+            .ids()
+            .filter(|&id| occupied[regions.cells(id)[0]])
+            .map(|id| regions.area(id))
+            .max()
+            .unwrap_or(0) as f64
+    }
+}
+
+/// A frame picked by [`Swarm::best_frame`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub time: usize,
+    pub score: f64,
+    pub positions: Vec<Pos>,
+    /// The frame as drawn by [`Swarm::render`].
+    pub rendering: String,
+}
+
+impl Visualize for Frame {
+    fn render(&self) -> String {
+        self.rendering.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Swarm {
+    pub bounds: Bounds,
+    pub robots: Vec<Robot>,
+}
+
+impl Swarm {
+    #[must_use]
+    pub fn new(bounds: Bounds, robots: Vec<Robot>) -> Self {
+        Self { bounds, robots }
+    }
+
+    /// A swarm whose robots reach `positions` after `time` steps, each moving with the velocity at the
+    /// same index. Handy for constructing a known arrangement, e.g. in tests.
+    #[must_use]
+    pub fn arranged_at(
+        bounds: Bounds,
+        time: usize,
+        positions: &[Pos],
+        velocities: &[Vector],
+    ) -> Self {
+        let robots = positions
+            .iter()
+            .zip(velocities)
+            .map(|(&position, &velocity)| {
+                let back = Robot {
+                    position,
+                    velocity: (-velocity.0, -velocity.1),
+                };
+                Robot {
+                    position: back.position_at(bounds, time),
+                    velocity,
+                }
+            })
+            .collect();
+        Self::new(bounds, robots)
+    }
+
+    /// Number of steps after which every robot is back at its start, whatever its velocity.
+    #[must_use]
+    pub fn period(&self) -> usize {
+        lcm(self.bounds.width as i128, self.bounds.height as i128) as usize
+    }
+
+    /// Positions of all robots after `time` steps, in the order of the robots.
+    #[must_use]
+    pub fn positions_at(&self, time: usize) -> Vec<Pos> {
+        self.robots
+            .iter()
+            .map(|robot| robot.position_at(self.bounds, time))
+            .collect()
+    }
+
+    /// Draws one line per row, with the number of robots on each cell or `.` for empty cells.
+    /// Counts above nine are drawn as `+`.
+    #[must_use]
+    pub fn render(&self, positions: &[Pos]) -> String {
+        let mut counts: HashMap<Pos, u32> = HashMap::new();
+        for &pos in positions {
+            *counts.entry(pos).or_default() += 1;
+        }
+        Grid::new(self.bounds.width, self.bounds.height, ()).render(|pos, _| {
+            match counts.get(&pos) {
+                None => '.',
+                Some(&count) => char::from_digit(count, 10).unwrap_or('+'),
+            }
+        })
+    }
+
+    /// The highest scoring frame among the first `limit` steps, or one full period if that is shorter.
+    /// Ties go to the earliest frame.
+    ///
+    /// # Panics
+    /// If `limit` is zero.
+    #[must_use]
+    pub fn best_frame(&self, detector: &impl Detector, limit: usize) -> Frame {
+        assert!(limit > 0, "at least one frame has to be searched");
+        let mut best: Option<(usize, f64, Vec<Pos>)> = None;
+        for time in 0..limit.min(self.period()) {
+            let positions = self.positions_at(time);
+            let score = detector.score(&positions, self.bounds);
+            if best.as_ref().is_none_or(|&(_, best, _)| score > best) {
+                best = Some((time, score, positions));
+            }
+        }
+
+        let (time, score, positions) = best.unwrap();
+        Frame {
+            time,
+            score,
+            rendering: self.render(&positions),
+            positions,
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Detector, Entropy, LargestComponent, Robot, Swarm, Variance};
+    use crate::lattice::Bounds;
+
+    /// Four robots that meet in a 2x2 block at time 3 and scatter otherwise.
+    fn gathering() -> Swarm {
+        let targets = [(2, 2), (3, 2), (2, 3), (3, 3)];
+        let velocities = [(1, 2), (-2, 1), (3, -1), (-1, -2)];
+        Swarm::arranged_at(Bounds::new(7, 5), 3, &targets, &velocities)
+    }
+
+    #[test]
+    fn moves_in_closed_form() {
+        let bounds = Bounds::new(11, 7);
+        let robot = Robot {
+            position: (2, 4),
+            velocity: (2, -3),
+        };
+        assert_eq!(robot.position_at(bounds, 1), (4, 1));
+        assert_eq!(robot.position_at(bounds, 2), (6, 5));
+        assert_eq!(robot.position_at(bounds, 5), (1, 3));
+        assert_eq!(robot.position_at(bounds, 77), robot.position);
+
+        let swarm = gathering();
+        assert_eq!(swarm.period(), 35);
+        assert_eq!(swarm.positions_at(3 + 35), swarm.positions_at(3));
+    }
+
+    #[test]
+    fn finds_the_best_frame() {
+        let swarm = gathering();
+        for detector in [&Variance as &dyn Detector, &Entropy, &LargestComponent] {
+            let best = (0..swarm.period())
+                .max_by(|&a, &b| {
+                    let score = |t| detector.score(&swarm.positions_at(t), swarm.bounds);
+                    score(a).total_cmp(&score(b)).then(b.cmp(&a))
+                })
+                .unwrap();
+            assert_eq!(best, 3);
+        }
+
+        let frame = swarm.best_frame(&LargestComponent, 100);
+        assert_eq!((frame.time, frame.score), (3, 4.0));
+        assert_eq!(
+            frame.rendering,
+            ".......\n.......\n..11...\n..11...\n......."
+        );
+        // the search stops at the limit, even though the best frame comes later.
+        assert!(swarm.best_frame(&Variance, 3).time < 3);
+    }
+}
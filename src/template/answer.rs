@@ -0,0 +1,127 @@
+/// The value returned by a solution part.
+///
+/// Solutions may return any integer type or a string, the runner converts the result via [`From`].
+/// Integers are stored without narrowing: values that do not fit an `i128` (i.e. large `u128`s)
+/// and values built with [`Answer::big`] fall back to their decimal digits. Those are only compared and
+/// printed, there is no arithmetic on them.
+use std::fmt::{self, Display};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i128),
+    /// Decimal digits of an integer outside of the `i128` range, with an optional leading `-`.
+    Big(String),
+    Text(String),
+}
+
+impl Answer {
+    /// Creates an arbitrary-precision integer answer from its decimal representation.
+    /// Returns `None` if `digits` is not a (optionally negative) decimal number.
+    #[must_use]
+    pub fn big(digits: &str) -> Option<Self> {
+        let unsigned = digits.strip_prefix('-').unwrap_or(digits);
+        if unsigned.is_empty() || !unsigned.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        // keep values within range in their native representation so equal answers compare equal.
+        Some(match digits.parse::<i128>() {
+            Ok(value) => Answer::Integer(value),
+            Err(_) => {
+                let trimmed = unsigned.trim_start_matches('0');
+                if digits.starts_with('-') {
+                    Answer::Big(format!("-{trimmed}"))
+                } else {
+                    Answer::Big(trimmed.to_string())
+                }
+            }
+        })
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{value}"),
+            Answer::Big(digits) => write!(f, "{digits}"),
+            Answer::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::Integer(i128::from(value))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(u8, u16, u32, u64, i8, i16, i32, i64, i128);
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        // lossless: `usize` is at most 128 bits wide.
+        Answer::from(value as u128)
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(value: isize) -> Self {
+        Answer::Integer(value as i128)
+    }
+}
+
+impl From<u128> for Answer {
+    fn from(value: u128) -> Self {
+        i128::try_from(value).map_or_else(|_| Answer::Big(value.to_string()), Answer::Integer)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Answer;
+
+    #[test]
+    fn converts_integers_without_narrowing() {
+        assert_eq!(Answer::from(42_u8), Answer::Integer(42));
+        assert_eq!(Answer::from(-3_isize), Answer::Integer(-3));
+        assert_eq!(Answer::from(usize::MAX).to_string(), usize::MAX.to_string());
+        assert_eq!(Answer::from(i128::MIN).to_string(), i128::MIN.to_string());
+    }
+
+    #[test]
+    fn falls_back_to_big_integers() {
+        assert_eq!(Answer::from(u128::MAX), Answer::Big(u128::MAX.to_string()));
+        assert_eq!(Answer::big("00042"), Some(Answer::Integer(42)));
+        assert_eq!(
+            Answer::big("-0001234567890123456789012345678901234567890"),
+            Some(Answer::Big(
+                "-1234567890123456789012345678901234567890".into()
+            ))
+        );
+        assert_eq!(Answer::big("12a"), None);
+        assert_eq!(Answer::big("-"), None);
+    }
+
+    #[test]
+    fn displays_text() {
+        assert_eq!(Answer::from("4,6,3,5").to_string(), "4,6,3,5");
+    }
+}
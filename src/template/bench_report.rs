@@ -0,0 +1,683 @@
+/// Module that renders benchmark timings in different output formats.
+/// Markdown is written to the readme, and like the other formats to a file next to it on request.
+use std::{collections::HashMap, fmt::Write, fs, io, str::FromStr};
+
+use tinyjson::JsonValue;
+
+use crate::template::meta::PuzzleIndex;
+use crate::template::readme_benchmarks::get_path_for_bin;
+use crate::template::timings::{format_nanos, Timing, Timings};
+
+/// A measurement that can be shown for each part.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Column {
+    /// Time spent in the parser of a day, if it registered one with the `solution!` macro.
+    Parse,
+    Time,
+    Median,
+    Samples,
+    Memory,
+    /// The fastest implementation, if the day was benchmarked with `--variants`.
+    Variant,
+    Total,
+}
+
+impl FromStr for Column {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "parse" => Ok(Column::Parse),
+            "time" => Ok(Column::Time),
+            "median" => Ok(Column::Median),
+            "samples" => Ok(Column::Samples),
+            "memory" => Ok(Column::Memory),
+            "variant" => Ok(Column::Variant),
+            "total" => Ok(Column::Total),
+            _ => Err(format!(
+                "unknown column `{s}`, expecting one of parse, time, median, samples, memory, variant, total"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortBy {
+    Day,
+    /// Slowest day first.
+    Time,
+}
+
+impl FromStr for SortBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "day" => Ok(SortBy::Day),
+            "time" => Ok(SortBy::Time),
+            _ => Err(format!("unknown sort `{s}`, expecting day or time")),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Markdown,
+    Csv,
+    Json,
+    Svg,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "md" | "markdown" => Ok(Format::Markdown),
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            "svg" => Ok(Format::Svg),
+            _ => Err(format!(
+                "unknown format `{s}`, expecting one of md, csv, json, svg"
+            )),
+        }
+    }
+}
+
+impl Format {
+    /// Renderer for this format. Markdown tables are prefixed with a `##` heading.
+    pub fn renderer(self) -> Box<dyn Renderer> {
+        match self {
+            Format::Markdown => Box::new(Markdown {
+                prefix: "##".into(),
+            }),
+            Format::Csv => Box::new(Csv),
+            Format::Json => Box::new(Json),
+            Format::Svg => Box::new(Svg),
+        }
+    }
+}
+
+/// Options that control which columns are shown and in which order days are listed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReportOptions {
+    pub columns: Vec<Column>,
+    pub sort: SortBy,
+}
+
+impl Default for ReportOptions {
+    fn default() -> Self {
+        Self {
+            columns: vec![Column::Time],
+            sort: SortBy::Day,
+        }
+    }
+}
+
+/// Parses a comma-separated list, e.g. `time,median`.
+pub fn parse_list<T: FromStr<Err = String>>(s: &str) -> Result<Vec<T>, String> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|x| !x.is_empty())
+        .map(T::from_str)
+        .collect()
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A single cell of the report. Human-readable formats use `display`, machine-readable
+/// formats use the raw `values`, e.g. nanoseconds instead of `74.0ns`.
+pub struct Cell {
+    pub header: String,
+    pub display: Option<String>,
+    pub values: Vec<(String, Option<f64>)>,
+}
+
+/// A row of the report, i.e. the timings of one day.
+pub struct Row {
+    pub day: u8,
+    pub label: String,
+    pub title: Option<String>,
+    pub path: String,
+    pub part_nanos: [Option<f64>; 2],
+    pub total_nanos: f64,
+    pub cells: Vec<Cell>,
+}
+
+/// Timings prepared for rendering.
+pub struct Report {
+    pub rows: Vec<Row>,
+    pub total_millis: f64,
+}
+
+fn format_memory(bytes: u64) -> String {
+    #[allow(clippy::cast_precision_loss)]
+    let bytes_f = bytes as f64;
+    match bytes {
+        b if b < 1024 => format!("{b}B"),
+        b if b < 1024 * 1024 => format!("{:.1}KiB", bytes_f / 1024.0),
+        _ => format!("{:.1}MiB", bytes_f / (1024.0 * 1024.0)),
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn part_cell(timing: &Timing, part: u8, column: Column) -> Option<Cell> {
+    let p = timing.part(part);
+    let key = format!("part_{part}");
+
+    let cell = match column {
+        Column::Time => Cell {
+            header: format!("Part {part}"),
+            display: p.map(|p| format_nanos(p.nanos)),
+            values: vec![(format!("{key}_nanos"), p.map(|p| p.nanos))],
+        },
+        Column::Median => {
+            let median = p.and_then(|p| p.median_nanos);
+            let spread = p.and_then(|p| p.spread_nanos);
+            Cell {
+                header: format!("Part {part} median"),
+                display: median.map(|m| {
+                    format!(
+                        "{} ± {}",
+                        format_nanos(m),
+                        format_nanos(spread.unwrap_or(0.0))
+                    )
+                }),
+                values: vec![
+                    (format!("{key}_median_nanos"), median),
+                    (format!("{key}_spread_nanos"), spread),
+                ],
+            }
+        }
+        Column::Samples => {
+            let samples = p.and_then(|p| p.samples);
+            Cell {
+                header: format!("Part {part} samples"),
+                display: samples.map(|s| s.to_string()),
+                values: vec![(format!("{key}_samples"), samples.map(|s| s as f64))],
+            }
+        }
+        Column::Memory => {
+            let memory = p.and_then(|p| p.memory);
+            Cell {
+                header: format!("Part {part} memory"),
+                display: memory.map(format_memory),
+                values: vec![(format!("{key}_memory_bytes"), memory.map(|m| m as f64))],
+            }
+        }
+        // names are not numeric, so the variant is only shown in human-readable formats.
+        Column::Variant => Cell {
+            header: format!("Part {part} variant"),
+            display: p.and_then(|p| p.variant.clone()),
+            values: vec![],
+        },
+        Column::Parse | Column::Total => return None,
+    };
+
+    Some(cell)
+}
+
+impl Report {
+    pub fn new(timings: &Timings, index: &PuzzleIndex, options: &ReportOptions) -> Self {
+        let mut rows: Vec<Row> = timings
+            .data
+            .iter()
+            .map(|timing| {
+                let title = index.get(timing.day).and_then(|m| m.title.clone());
+                let day = timing.day.into_inner();
+
+                let mut cells: Vec<Cell> = vec![];
+                if options.columns.contains(&Column::Parse) {
+                    let parse = timing.parse.as_ref().map(|p| p.nanos);
+                    cells.push(Cell {
+                        header: "Parse".into(),
+                        display: parse.map(format_nanos),
+                        values: vec![("parse_nanos".into(), parse)],
+                    });
+                }
+
+                cells.extend([1, 2].iter().flat_map(|&part| {
+                    options
+                        .columns
+                        .iter()
+                        .filter_map(move |&column| part_cell(timing, part, column))
+                }));
+
+                if options.columns.contains(&Column::Total) {
+                    cells.push(Cell {
+                        header: "Total".into(),
+                        display: Some(format_nanos(timing.total_nanos)),
+                        values: vec![("total_nanos".into(), Some(timing.total_nanos))],
+                    });
+                }
+
+                Row {
+                    day,
+                    label: match &title {
+                        Some(title) => format!("Day {day}: {title}"),
+                        None => format!("Day {day}"),
+                    },
+                    title,
+                    path: get_path_for_bin(timing.day),
+                    part_nanos: [
+                        timing.part_1.as_ref().map(|p| p.nanos),
+                        timing.part_2.as_ref().map(|p| p.nanos),
+                    ],
+                    total_nanos: timing.total_nanos,
+                    cells,
+                }
+            })
+            .collect();
+
+        match options.sort {
+            SortBy::Day => rows.sort_by_key(|r| r.day),
+            SortBy::Time => rows.sort_by(|a, b| b.total_nanos.total_cmp(&a.total_nanos)),
+        }
+
+        Report {
+            rows,
+            total_millis: timings.total_millis(),
+        }
+    }
+
+    fn headers(&self) -> Vec<&str> {
+        self.rows.first().map_or_else(Vec::new, |row| {
+            row.cells.iter().map(|c| c.header.as_str()).collect()
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Renders a report into a specific output format.
+pub trait Renderer {
+    fn render(&self, report: &Report) -> String;
+
+    /// File extension used when the output is written to disk.
+    fn extension(&self) -> &'static str;
+}
+
+pub struct Markdown {
+    pub prefix: String,
+}
+
+impl Renderer for Markdown {
+    fn render(&self, report: &Report) -> String {
+        let headers = report.headers();
+        let headers = if headers.is_empty() {
+            vec!["Part 1", "Part 2"]
+        } else {
+            headers
+        };
+
+        let mut lines: Vec<String> = vec![
+            format!("{} Benchmarks", self.prefix),
+            String::new(),
+            format!("| Day | {} |", headers.join(" | ")),
+            format!("| :---: |{}", " :---: |".repeat(headers.len())),
+        ];
+
+        for row in &report.rows {
+            let cells: Vec<String> = row
+                .cells
+                .iter()
+                .map(|c| format!("`{}`", c.display.as_deref().unwrap_or("-")))
+                .collect();
+            lines.push(format!(
+                "| [{}]({}) | {} |",
+                row.label,
+                row.path,
+                cells.join(" | ")
+            ));
+        }
+
+        lines.push(String::new());
+        lines.push(format!("**Total: {:.2}ms**", report.total_millis));
+
+        lines.join("\n")
+    }
+
+    fn extension(&self) -> &'static str {
+        "md"
+    }
+}
+
+pub struct Csv;
+
+fn csv_escape(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+impl Renderer for Csv {
+    fn render(&self, report: &Report) -> String {
+        let mut out = String::new();
+
+        let keys: Vec<&str> = report.rows.first().map_or_else(Vec::new, |row| {
+            row.cells
+                .iter()
+                .flat_map(|c| c.values.iter().map(|(key, _)| key.as_str()))
+                .collect()
+        });
+        let _ = writeln!(out, "day,title,{}", keys.join(","));
+
+        for row in &report.rows {
+            let values: Vec<String> = row
+                .cells
+                .iter()
+                .flat_map(|c| c.values.iter())
+                .map(|(_, value)| value.map(|v| v.to_string()).unwrap_or_default())
+                .collect();
+            let _ = writeln!(
+                out,
+                "{},{},{}",
+                row.day,
+                csv_escape(row.title.as_deref().unwrap_or_default()),
+                values.join(",")
+            );
+        }
+
+        out
+    }
+
+    fn extension(&self) -> &'static str {
+        "csv"
+    }
+}
+
+pub struct Json;
+
+impl Renderer for Json {
+    fn render(&self, report: &Report) -> String {
+        let rows = report
+            .rows
+            .iter()
+            .map(|row| {
+                let mut map: HashMap<String, JsonValue> = HashMap::new();
+                map.insert("day".into(), JsonValue::Number(f64::from(row.day)));
+                map.insert(
+                    "title".into(),
+                    row.title.clone().map_or(JsonValue::Null, JsonValue::String),
+                );
+                for (key, value) in row.cells.iter().flat_map(|c| c.values.iter()) {
+                    map.insert(
+                        key.clone(),
+                        value.map_or(JsonValue::Null, JsonValue::Number),
+                    );
+                }
+                JsonValue::Object(map)
+            })
+            .collect();
+
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+        map.insert("data".into(), JsonValue::Array(rows));
+        map.insert(
+            "total_millis".into(),
+            JsonValue::Number(report.total_millis),
+        );
+
+        JsonValue::Object(map).format().unwrap_or_default()
+    }
+
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+}
+
+/// Horizontal bar chart of the total run time per day.
+pub struct Svg;
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl Renderer for Svg {
+    fn render(&self, report: &Report) -> String {
+        const WIDTH: f64 = 720.0;
+        const LABEL_WIDTH: f64 = 260.0;
+        const VALUE_WIDTH: f64 = 90.0;
+        const ROW_HEIGHT: f64 = 24.0;
+        const HEADER_HEIGHT: f64 = 36.0;
+        const PART_COLORS: [&str; 2] = ["#c0392b", "#27ae60"];
+
+        #[allow(clippy::cast_precision_loss)]
+        let height = HEADER_HEIGHT + ROW_HEIGHT * report.rows.len() as f64 + 12.0;
+        let bar_space = WIDTH - LABEL_WIDTH - VALUE_WIDTH;
+        let max = report
+            .rows
+            .iter()
+            .map(|r| r.total_nanos)
+            .fold(0_f64, f64::max);
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{height}" viewBox="0 0 {WIDTH} {height}" font-family="sans-serif" font-size="13">"#
+        );
+        let _ = writeln!(
+            out,
+            r##"<rect width="100%" height="100%" fill="#ffffff"/>"##
+        );
+        let _ = writeln!(
+            out,
+            r#"<text x="8" y="22" font-size="15" font-weight="bold">Benchmarks (total: {:.2}ms)</text>"#,
+            report.total_millis
+        );
+        for (i, color) in PART_COLORS.iter().enumerate() {
+            #[allow(clippy::cast_precision_loss)]
+            let x = WIDTH - 150.0 + 75.0 * i as f64;
+            let _ = writeln!(
+                out,
+                r#"<rect x="{x}" y="12" width="12" height="12" fill="{color}"/><text x="{}" y="22">Part {}</text>"#,
+                x + 16.0,
+                i + 1
+            );
+        }
+
+        for (i, row) in report.rows.iter().enumerate() {
+            #[allow(clippy::cast_precision_loss)]
+            let y = HEADER_HEIGHT + ROW_HEIGHT * i as f64;
+            let scale = |nanos: f64| {
+                if max > 0.0 {
+                    nanos / max * bar_space
+                } else {
+                    0.0
+                }
+            };
+
+            let _ = writeln!(
+                out,
+                r#"<text x="8" y="{:.1}">{}</text>"#,
+                y + 16.0,
+                xml_escape(&row.label)
+            );
+
+            // one stacked segment per part.
+            let mut x = LABEL_WIDTH;
+            for (nanos, color) in row.part_nanos.iter().zip(PART_COLORS) {
+                let Some(nanos) = nanos else {
+                    continue;
+                };
+                let width = scale(*nanos).max(1.0);
+                let _ = writeln!(
+                    out,
+                    r#"<rect x="{x:.1}" y="{:.1}" width="{width:.1}" height="{:.1}" fill="{color}"/>"#,
+                    y + 4.0,
+                    ROW_HEIGHT - 8.0
+                );
+                x += width;
+            }
+
+            let _ = writeln!(
+                out,
+                r#"<text x="{:.1}" y="{:.1}">{}</text>"#,
+                x + 6.0,
+                y + 16.0,
+                format_nanos(row.total_nanos)
+            );
+        }
+
+        out.push_str("</svg>\n");
+        out
+    }
+
+    fn extension(&self) -> &'static str {
+        "svg"
+    }
+}
+
+/// Write a report next to the readme, e.g. `benchmarks.svg`.
+pub fn write_file(report: &Report, format: Format) -> Result<String, io::Error> {
+    let renderer = format.renderer();
+    let path = format!("benchmarks.{}", renderer.extension());
+    fs::write(&path, renderer.render(report))?;
+    Ok(path)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{parse_list, Column, Format, Report, ReportOptions, SortBy};
+    use crate::{
+        day,
+        template::meta::PuzzleIndex,
+        template::timings::{PartTiming, Timing, Timings},
+    };
+
+    fn get_mock_timings() -> Timings {
+        Timings {
+            data: vec![
+                Timing {
+                    day: day!(1),
+                    parse: Some(PartTiming::from_nanos(1.5e+6)),
+                    part_1: Some(PartTiming {
+                        nanos: 1e+7,
+                        samples: Some(100),
+                        median_nanos: Some(9.5e+6),
+                        spread_nanos: Some(5e+5),
+                        memory: Some(2048),
+                        variant: Some("search".into()),
+                    }),
+                    part_2: Some(PartTiming::from_nanos(2e+7)),
+                    total_nanos: 3e+7,
+                },
+                Timing {
+                    day: day!(2),
+                    parse: None,
+                    part_1: Some(PartTiming::from_nanos(3e+7)),
+                    part_2: None,
+                    total_nanos: 3e+7 + 1.0,
+                },
+            ],
+        }
+    }
+
+    fn render(format: Format, options: &ReportOptions) -> String {
+        let report = Report::new(&get_mock_timings(), &PuzzleIndex::default(), options);
+        format.renderer().render(&report)
+    }
+
+    #[test]
+    fn parses_options() {
+        assert_eq!(
+            parse_list::<Column>("time, median,memory").unwrap(),
+            vec![Column::Time, Column::Median, Column::Memory]
+        );
+        assert!(parse_list::<Column>("time,speed").is_err());
+        assert_eq!(parse_list::<Format>("svg").unwrap(), vec![Format::Svg]);
+    }
+
+    #[test]
+    fn renders_markdown_columns() {
+        let options = ReportOptions {
+            columns: vec![Column::Time, Column::Median, Column::Memory, Column::Total],
+            sort: SortBy::Day,
+        };
+        let md = render(Format::Markdown, &options);
+        assert!(md.contains("| Day | Part 1 | Part 1 median | Part 1 memory | Part 2 | Part 2 median | Part 2 memory | Total |"));
+        assert!(md.contains("| :---: | :---: | :---: | :---: | :---: | :---: | :---: | :---: |"));
+        assert!(md.contains("| [Day 1](./src/bin/01.rs) | `10.0ms` | `9.5ms ± 500.0µs` | `2.0KiB` | `20.0ms` | `-` | `-` | `30.0ms` |"));
+    }
+
+    #[test]
+    fn renders_parse_column() {
+        let options = ReportOptions {
+            columns: vec![Column::Parse, Column::Time],
+            sort: SortBy::Day,
+        };
+        let md = render(Format::Markdown, &options);
+        assert!(md.contains("| Day | Parse | Part 1 | Part 2 |"));
+        assert!(md.contains("| [Day 1](./src/bin/01.rs) | `1.5ms` | `10.0ms` | `20.0ms` |"));
+        assert!(md.contains("| [Day 2](./src/bin/02.rs) | `-` | `30.0ms` | `-` |"));
+
+        let csv = render(Format::Csv, &options);
+        assert!(csv.starts_with("day,title,parse_nanos,part_1_nanos,part_2_nanos\n"));
+    }
+
+    #[test]
+    fn renders_variant_column() {
+        let options = ReportOptions {
+            columns: vec![Column::Time, Column::Variant],
+            sort: SortBy::Day,
+        };
+        let md = render(Format::Markdown, &options);
+        assert!(md.contains("| Day | Part 1 | Part 1 variant | Part 2 | Part 2 variant |"));
+        assert!(md.contains("| [Day 1](./src/bin/01.rs) | `10.0ms` | `search` | `20.0ms` | `-` |"));
+
+        let csv = render(Format::Csv, &options);
+        assert!(csv.starts_with("day,title,part_1_nanos,part_2_nanos\n"));
+    }
+
+    #[test]
+    fn sorts_by_time() {
+        let options = ReportOptions {
+            sort: SortBy::Time,
+            ..ReportOptions::default()
+        };
+        let md = render(Format::Markdown, &options);
+        assert!(md.find("[Day 2]").unwrap() < md.find("[Day 1]").unwrap());
+    }
+
+    #[test]
+    fn renders_csv() {
+        let options = ReportOptions {
+            columns: vec![Column::Time, Column::Samples],
+            sort: SortBy::Day,
+        };
+        let csv = render(Format::Csv, &options);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "day,title,part_1_nanos,part_1_samples,part_2_nanos,part_2_samples"
+        );
+        assert_eq!(lines[1], "1,,10000000,100,20000000,");
+        assert_eq!(lines[2], "2,,30000000,,,");
+    }
+
+    #[test]
+    fn renders_json() {
+        let json = render(Format::Json, &ReportOptions::default());
+        let value: tinyjson::JsonValue = json.parse().unwrap();
+        let data: &Vec<tinyjson::JsonValue> = value["data"].get().unwrap();
+        assert_eq!(data.len(), 2);
+        let part_1: &f64 = data[0]["part_1_nanos"].get().unwrap();
+        assert_eq!(*part_1, 1e+7);
+        assert!(data[1]["part_2_nanos"].is_null());
+    }
+
+    #[test]
+    fn renders_svg() {
+        let svg = render(Format::Svg, &ReportOptions::default());
+        assert!(svg.starts_with("<svg"));
+        // background, two legend entries, three part segments.
+        assert_eq!(svg.matches("<rect").count(), 6);
+        assert!(svg.contains("Day 1"));
+    }
+}
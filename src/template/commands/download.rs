@@ -1,4 +1,4 @@
-use crate::template::{aoc_cli, Day};
+use crate::template::{aoc_cli, meta, Day};
 use std::process;
 
 pub fn handle(day: Day) {
@@ -11,4 +11,9 @@ pub fn handle(day: Day) {
         eprintln!("failed to call aoc-cli: {e}");
         process::exit(1);
     };
+
+    match meta::update_from_puzzle(day) {
+        Ok(meta) => println!("🎄 Indexed puzzle \"{}\".", meta.heading()),
+        Err(e) => eprintln!("failed to index puzzle metadata: {e}"),
+    }
 }
@@ -1,6 +1,6 @@
 use std::process;
 
-use crate::template::{aoc_cli, Day};
+use crate::template::{aoc_cli, meta, Day};
 
 pub fn handle(day: Day) {
     if aoc_cli::check().is_err() {
@@ -12,4 +12,8 @@ pub fn handle(day: Day) {
         eprintln!("failed to call aoc-cli: {e}");
         process::exit(1);
     };
+
+    if let Err(e) = meta::update_from_puzzle(day) {
+        eprintln!("failed to index puzzle metadata: {e}");
+    }
 }
@@ -0,0 +1,22 @@
+use std::process;
+
+use crate::template::{aoc_cli, meta, readme_stars};
+
+pub fn handle() {
+    let index = match meta::refresh_index() {
+        Ok(index) => index,
+        Err(e) => {
+            eprintln!("Failed to update puzzle index: {e}");
+            process::exit(1);
+        }
+    };
+
+    match readme_stars::update(&index, aoc_cli::get_year()) {
+        Ok(true) => println!("Stored updated stars."),
+        Ok(false) => println!("No recorded answers yet, leaving stars untouched."),
+        Err(_) => {
+            eprintln!("Failed to store updated stars.");
+            process::exit(1);
+        }
+    }
+}
@@ -2,7 +2,17 @@ use std::process::{Command, Stdio};
 
 use crate::template::Day;
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
+#[allow(clippy::too_many_arguments)]
+pub fn handle(
+    day: Day,
+    release: bool,
+    dhat: bool,
+    checked: bool,
+    viz: bool,
+    gif: Option<String>,
+    variant: Option<String>,
+    submit_part: Option<u8>,
+) {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
     if dhat {
@@ -12,12 +22,28 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
             "--features".to_string(),
             "dhat-heap".to_string(),
         ]);
+    } else if checked {
+        cmd_args.extend(["--profile".to_string(), "checked".to_string()]);
     } else if release {
         cmd_args.push("--release".to_string());
     }
 
     cmd_args.push("--".to_string());
 
+    if viz {
+        cmd_args.push("--viz".to_string());
+    }
+
+    if let Some(gif) = gif {
+        cmd_args.push("--gif".to_string());
+        cmd_args.push(gif);
+    }
+
+    if let Some(variant) = variant {
+        cmd_args.push("--variant".to_string());
+        cmd_args.push(variant);
+    }
+
     if let Some(submit_part) = submit_part {
         cmd_args.push("--submit".to_string());
         cmd_args.push(submit_part.to_string());
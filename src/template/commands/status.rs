@@ -0,0 +1,119 @@
+use std::{fs, path::Path};
+
+use crate::template::meta::{self, PuzzleIndex};
+use crate::template::run_multi::get_path_for_bin;
+use crate::template::{all_days, aoc_cli, Day, ANSI_BOLD, ANSI_RESET};
+
+/// State of the local files belonging to a day.
+struct DayStatus {
+    day: Day,
+    title: String,
+    stars: usize,
+    has_input: bool,
+    examples: usize,
+    has_solution: bool,
+    answers: Vec<String>,
+}
+
+impl DayStatus {
+    fn is_empty(&self) -> bool {
+        self.title.is_empty() && !self.has_input && self.examples == 0 && !self.has_solution
+    }
+}
+
+fn is_non_empty_file(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|m| m.is_file() && m.len() > 0)
+}
+
+/// Counts example files for a day, i.e. `DD.txt` and `DD-N.txt`.
+fn count_examples(day: Day) -> usize {
+    let Ok(entries) = fs::read_dir("data/examples") else {
+        return 0;
+    };
+
+    let day = day.to_string();
+
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.strip_suffix(".txt")
+                .is_some_and(|stem| stem == day || stem.starts_with(&format!("{day}-")))
+                && is_non_empty_file(&entry.path())
+        })
+        .count()
+}
+
+fn collect_status(index: &PuzzleIndex, day: Day) -> DayStatus {
+    let meta = index.get(day);
+
+    DayStatus {
+        day,
+        title: meta.and_then(|m| m.title.clone()).unwrap_or_default(),
+        stars: meta.map_or(0, |m| m.stars()),
+        has_input: is_non_empty_file(Path::new(&aoc_cli::get_input_path(day))),
+        examples: count_examples(day),
+        has_solution: Path::new(&get_path_for_bin(day)).exists(),
+        answers: meta
+            .map(|m| {
+                [m.answer(1), m.answer(2)]
+                    .iter()
+                    .map(|a| a.unwrap_or("-").to_string())
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+fn check(value: bool) -> &'static str {
+    if value {
+        "✔"
+    } else {
+        "✖"
+    }
+}
+
+pub fn handle() {
+    let index = meta::refresh_index().unwrap_or_else(|e| {
+        eprintln!("failed to update puzzle index: {e}");
+        PuzzleIndex::read_from_file()
+    });
+
+    let rows: Vec<DayStatus> = all_days()
+        .map(|day| collect_status(&index, day))
+        .filter(|status| !status.is_empty())
+        .collect();
+
+    if rows.is_empty() {
+        println!("No days scaffolded yet.");
+        return;
+    }
+
+    let title_width = rows
+        .iter()
+        .map(|r| r.title.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("Title".len());
+
+    println!(
+        "{ANSI_BOLD}Day  Stars  {:<title_width$}  Input  Examples  Solution  Answers{ANSI_RESET}",
+        "Title"
+    );
+
+    for row in &rows {
+        let stars = format!("{:<2}", "*".repeat(row.stars));
+        println!(
+            "{}   {stars}     {:<title_width$}  {}      {:<8}  {}         {}",
+            row.day,
+            row.title,
+            check(row.has_input),
+            row.examples,
+            check(row.has_solution),
+            row.answers.join(" / "),
+        );
+    }
+
+    let total_stars: usize = rows.iter().map(|r| r.stars).sum();
+    println!("\n{ANSI_BOLD}Total:{ANSI_RESET} {total_stars} stars");
+}
@@ -1,10 +1,19 @@
 use std::collections::HashSet;
 
+use crate::template::bench_report::{self, Format, Report, ReportOptions};
 use crate::template::run_multi::run_multi;
 use crate::template::timings::Timings;
-use crate::template::{all_days, readme_benchmarks, Day};
+use crate::template::{all_days, aoc_cli, meta, readme_benchmarks, readme_stars, Day};
 
-pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
+pub fn handle(
+    day: Option<Day>,
+    run_all: bool,
+    store: bool,
+    track_memory: bool,
+    variants: bool,
+    options: &ReportOptions,
+    formats: &[Format],
+) {
     let stored_timings = Timings::read_from_file();
 
     let days_to_run = day.map_or_else(
@@ -21,14 +30,14 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
         |day| HashSet::from([day]),
     );
 
-    let timings = run_multi(&days_to_run, true, true).unwrap();
+    let timings = run_multi(&days_to_run, true, false, true, track_memory, variants).unwrap();
 
     if store {
         let merged_timings = stored_timings.merge(&timings);
         merged_timings.store_file().unwrap();
 
         println!();
-        match readme_benchmarks::update(merged_timings) {
+        match readme_benchmarks::update(&merged_timings, options) {
             Ok(()) => {
                 println!("Stored updated benchmarks.");
             }
@@ -36,5 +45,22 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
                 eprintln!("Failed to store updated benchmarks.");
             }
         }
+
+        let index = meta::PuzzleIndex::read_from_file();
+        let report = Report::new(&merged_timings, &index, options);
+        for format in formats {
+            match bench_report::write_file(&report, *format) {
+                Ok(path) => println!("Stored benchmarks to \"{path}\"."),
+                Err(e) => eprintln!("Failed to store benchmarks as {format:?}: {e}"),
+            }
+        }
+
+        if let Ok(index) = meta::refresh_index() {
+            match readme_stars::update(&index, aoc_cli::get_year()) {
+                Ok(true) => println!("Stored updated stars."),
+                Ok(false) => {}
+                Err(_) => eprintln!("Failed to store updated stars."),
+            }
+        }
     }
 }
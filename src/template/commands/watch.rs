@@ -0,0 +1,220 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::template::{
+    aoc_cli,
+    run_multi::{child_commands, get_path_for_bin},
+    Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const ANSI_CLEAR: &str = "\x1b[2J\x1b[H";
+
+type Snapshot = HashMap<PathBuf, SystemTime>;
+
+/// Files that trigger a re-run: the solution, all examples of the day and the input.
+fn watched_files(day: Day) -> Vec<PathBuf> {
+    let mut files = vec![
+        PathBuf::from(get_path_for_bin(day)),
+        PathBuf::from(aoc_cli::get_input_path(day)),
+    ];
+
+    if let Ok(entries) = fs::read_dir("data/examples") {
+        let day = day.to_string();
+        files.extend(
+            entries
+                .filter_map(Result::ok)
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.file_stem()
+                        .and_then(|s| s.to_str())
+                        .is_some_and(|stem| stem == day || stem.starts_with(&format!("{day}-")))
+                }),
+        );
+    }
+
+    files
+}
+
+fn snapshot(day: Day) -> Snapshot {
+    watched_files(day)
+        .into_iter()
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect()
+}
+
+/// Runs the day's tests, forwarding their output. Returns whether they passed.
+fn run_tests(day: Day) -> bool {
+    Command::new("cargo")
+        .args(["test", "--quiet", "--bin", &day.to_string()])
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Extracts the answers printed by a solution bin, indexed by part.
+/// Multi-line answers are joined by newlines, unsolved parts are `None`.
+fn parse_answers(output: &[String]) -> Vec<(u8, Option<String>)> {
+    let mut answers: Vec<(u8, Option<String>)> = vec![];
+    let mut multi_line: Option<String> = None;
+
+    for line in output {
+        // the runner prints an intermediate result before overwriting the line with `\r`.
+        let line = line.rsplit('\r').next().unwrap_or_default();
+
+        let part = line
+            .strip_prefix("Part ")
+            .and_then(|l| l.split_once(": "))
+            .and_then(|(part, rest)| Some((part.parse::<u8>().ok()?, rest)));
+
+        match part {
+            Some((part, rest)) => {
+                if let Some(answer) = multi_line.take() {
+                    answers.last_mut().unwrap().1 = Some(answer.trim_end().to_string());
+                }
+
+                let answer = if rest.starts_with('▼') {
+                    multi_line = Some(String::new());
+                    None
+                } else {
+                    rest.split_once(ANSI_BOLD)
+                        .and_then(|(_, r)| r.split_once(ANSI_RESET))
+                        .map(|(answer, _)| answer.to_string())
+                };
+
+                answers.push((part, answer));
+            }
+            None => {
+                if let Some(answer) = multi_line.as_mut() {
+                    if !answer.is_empty() {
+                        answer.push('\n');
+                    }
+                    answer.push_str(line);
+                }
+            }
+        }
+    }
+
+    if let Some(answer) = multi_line {
+        answers.last_mut().unwrap().1 = Some(answer.trim_end().to_string());
+    }
+
+    answers
+}
+
+/// Describes how each answer changed relative to the previous run.
+fn diff_answers(
+    previous: Option<&[(u8, Option<String>)]>,
+    current: &[(u8, Option<String>)],
+) -> Vec<String> {
+    let display = |answer: &Option<String>| match answer {
+        Some(a) if a.contains('\n') => format!("<{} lines>", a.lines().count()),
+        Some(a) => a.clone(),
+        None => "✖".into(),
+    };
+
+    current
+        .iter()
+        .map(|(part, answer)| {
+            let before = previous.and_then(|p| p.iter().find(|(p, _)| p == part));
+            match before {
+                None => format!("Part {part}: {} (new)", display(answer)),
+                Some((_, before)) if before == answer => {
+                    format!("Part {part}: {} (unchanged)", display(answer))
+                }
+                Some((_, before)) => {
+                    format!("Part {part}: {} → {}", display(before), display(answer))
+                }
+            }
+        })
+        .collect()
+}
+
+pub fn handle(day: Day, release: bool) {
+    let mut previous: Option<Vec<(u8, Option<String>)>> = None;
+    let mut last_snapshot: Option<Snapshot> = None;
+
+    loop {
+        let current_snapshot = snapshot(day);
+
+        if last_snapshot.as_ref() != Some(&current_snapshot) {
+            last_snapshot = Some(current_snapshot);
+
+            print!("{ANSI_CLEAR}");
+            println!("{ANSI_BOLD}Day {day}{ANSI_RESET} {ANSI_ITALIC}(watching for changes, Ctrl-C to stop){ANSI_RESET}\n");
+
+            let tests_passed = run_tests(day);
+            println!();
+
+            let output =
+                child_commands::run_solution(day, false, release, false, false, false).unwrap();
+            let answers = parse_answers(&output);
+
+            println!(
+                "\n{ANSI_BOLD}Tests:{ANSI_RESET} {}",
+                if tests_passed { "passed" } else { "failed" }
+            );
+            for line in diff_answers(previous.as_deref(), &answers) {
+                println!("{line}");
+            }
+
+            previous = Some(answers);
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{diff_answers, parse_answers};
+    use crate::template::{ANSI_BOLD, ANSI_RESET};
+
+    fn answer(part: u8, answer: &str) -> String {
+        format!("Part {part}: {ANSI_BOLD}{answer}{ANSI_RESET}\rPart {part}: {ANSI_BOLD}{answer}{ANSI_RESET} (1.0ms)")
+    }
+
+    #[test]
+    fn parses_answers() {
+        let output = [answer(1, "42"), "Part 2: ✖\rPart 2: ✖             ".into()];
+        assert_eq!(
+            parse_answers(&output),
+            vec![(1, Some("42".into())), (2, None)]
+        );
+    }
+
+    #[test]
+    fn parses_multi_line_answers() {
+        let output = [
+            "Part 1: ▼ \rPart 1: ▼  (1.0ms)".into(),
+            "#..".into(),
+            ".#.".into(),
+            answer(2, "7"),
+        ];
+        assert_eq!(
+            parse_answers(&output),
+            vec![(1, Some("#..\n.#.".into())), (2, Some("7".into()))]
+        );
+    }
+
+    #[test]
+    fn diffs_answers() {
+        let previous = vec![(1, Some("42".into())), (2, None)];
+        let current = vec![(1, Some("42".into())), (2, Some("7".into()))];
+        assert_eq!(
+            diff_answers(Some(&previous), &current),
+            vec!["Part 1: 42 (unchanged)", "Part 2: ✖ → 7"]
+        );
+        assert_eq!(diff_answers(None, &previous)[0], "Part 1: 42 (new)");
+    }
+}
@@ -0,0 +1,352 @@
+/// Module that keeps an index of puzzle metadata (titles, part two availability and answers).
+/// The index is built from the puzzle descriptions that aoc-cli writes to `data/puzzles`.
+use std::{collections::HashMap, fs, io::Error, str::FromStr};
+use tinyjson::JsonValue;
+
+use crate::template::{all_days, aoc_cli, Day};
+
+static META_FILE_PATH: &str = "./data/meta.json";
+
+/// Represents the known metadata of a single puzzle.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PuzzleMeta {
+    pub day: Day,
+    pub title: Option<String>,
+    pub part_two: bool,
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+impl PuzzleMeta {
+    pub fn new(day: Day) -> Self {
+        Self {
+            day,
+            title: None,
+            part_two: false,
+            part_1: None,
+            part_2: None,
+        }
+    }
+
+    /// Extract metadata from a puzzle description as written by aoc-cli.
+    pub fn from_puzzle(day: Day, puzzle: &str) -> Self {
+        // aoc-cli escapes leading dashes, e.g. `\--- Day 1: Title ---`.
+        let puzzle = puzzle.replace('\\', "");
+
+        let title_re = regex::Regex::new(r"--- Day \d+: (.+?) ---").unwrap();
+        let answer_re = regex::Regex::new(r"Your puzzle answer was `([^`]+)`").unwrap();
+
+        let title = title_re
+            .captures(&puzzle)
+            .map(|caps| caps[1].trim().to_string());
+
+        let mut answers = answer_re
+            .captures_iter(&puzzle)
+            .map(|caps| caps[1].to_string());
+
+        Self {
+            day,
+            title,
+            part_two: puzzle.contains("--- Part Two ---"),
+            part_1: answers.next(),
+            part_2: answers.next(),
+        }
+    }
+
+    /// Get the recorded answer for a part, if any.
+    pub fn answer(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part_1.as_deref(),
+            2 => self.part_2.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Number of parts that have a recorded answer.
+    pub fn stars(&self) -> usize {
+        usize::from(self.part_1.is_some()) + usize::from(self.part_2.is_some())
+    }
+
+    /// Heading used when listing the day, e.g. `Day 01: Historian Hysteria`.
+    pub fn heading(&self) -> String {
+        match &self.title {
+            Some(title) => format!("Day {}: {title}", self.day),
+            None => format!("Day {}", self.day),
+        }
+    }
+
+    /// Merge newer metadata into `self`. Fields missing in `new` keep their previous value.
+    fn merge(&self, new: &Self) -> Self {
+        Self {
+            day: self.day,
+            title: new.title.clone().or_else(|| self.title.clone()),
+            part_two: self.part_two || new.part_two,
+            part_1: new.part_1.clone().or_else(|| self.part_1.clone()),
+            part_2: new.part_2.clone().or_else(|| self.part_2.clone()),
+        }
+    }
+}
+
+/// Represents the metadata of a set of puzzles.
+/// Can be serialized from / to JSON.
+#[derive(Clone, Debug, Default)]
+pub struct PuzzleIndex {
+    pub data: Vec<PuzzleMeta>,
+}
+
+impl PuzzleIndex {
+    /// Dehydrate the index to a JSON file.
+    pub fn store_file(&self) -> Result<(), Error> {
+        let json = JsonValue::from(self.clone());
+        let mut file = fs::File::create(META_FILE_PATH)?;
+        json.format_to(&mut file)
+    }
+
+    /// Rehydrate the index from a JSON file. If not present, returns an empty index.
+    pub fn read_from_file() -> Self {
+        fs::read_to_string(META_FILE_PATH)
+            .map_err(|x| x.to_string())
+            .and_then(PuzzleIndex::try_from)
+            .unwrap_or_default()
+    }
+
+    pub fn get(&self, day: Day) -> Option<&PuzzleMeta> {
+        self.data.iter().find(|m| m.day == day)
+    }
+
+    /// Heading for a day, falling back to `Day DD` if the title is unknown.
+    pub fn heading(&self, day: Day) -> String {
+        self.get(day)
+            .map_or_else(|| PuzzleMeta::new(day).heading(), PuzzleMeta::heading)
+    }
+
+    /// Insert metadata for a day, merging it with existing metadata if present.
+    pub fn upsert(&mut self, meta: PuzzleMeta) {
+        match self.data.iter_mut().find(|m| m.day == meta.day) {
+            Some(existing) => *existing = existing.merge(&meta),
+            None => self.data.push(meta),
+        }
+        self.data.sort_unstable_by_key(|m| m.day);
+    }
+}
+
+/// Read the puzzle description of a day and update the index file with its metadata.
+pub fn update_from_puzzle(day: Day) -> Result<PuzzleMeta, Error> {
+    let puzzle = fs::read_to_string(aoc_cli::get_puzzle_path(day))?;
+    let meta = PuzzleMeta::from_puzzle(day, &puzzle);
+
+    let mut index = PuzzleIndex::read_from_file();
+    index.upsert(meta);
+    index.store_file()?;
+
+    Ok(index
+        .get(day)
+        .cloned()
+        .unwrap_or_else(|| PuzzleMeta::new(day)))
+}
+
+/// Record an answer that was accepted by the website.
+pub fn record_answer(day: Day, part: u8, answer: &str) -> Result<(), Error> {
+    let mut meta = PuzzleMeta::new(day);
+    match part {
+        1 => meta.part_1 = Some(answer.into()),
+        2 => meta.part_2 = Some(answer.into()),
+        _ => return Ok(()),
+    }
+
+    let mut index = PuzzleIndex::read_from_file();
+    index.upsert(meta);
+    index.store_file()
+}
+
+/// Re-index every puzzle description present in `data/puzzles` and update the index file.
+pub fn refresh_index() -> Result<PuzzleIndex, Error> {
+    let mut index = PuzzleIndex::read_from_file();
+
+    for day in all_days() {
+        if let Ok(puzzle) = fs::read_to_string(aoc_cli::get_puzzle_path(day)) {
+            index.upsert(PuzzleMeta::from_puzzle(day, &puzzle));
+        }
+    }
+
+    if !index.data.is_empty() {
+        index.store_file()?;
+    }
+
+    Ok(index)
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<PuzzleIndex> for JsonValue {
+    fn from(value: PuzzleIndex) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<String> for PuzzleIndex {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json_data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(PuzzleIndex {
+            data: json_data
+                .iter()
+                .map(PuzzleMeta::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn optional_string(value: Option<&String>) -> JsonValue {
+    value.map_or(JsonValue::Null, |x| JsonValue::String(x.clone()))
+}
+
+impl From<&PuzzleMeta> for JsonValue {
+    fn from(value: &PuzzleMeta) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("day".into(), JsonValue::String(value.day.to_string()));
+        map.insert("title".into(), optional_string(value.title.as_ref()));
+        map.insert("part_two".into(), JsonValue::Boolean(value.part_two));
+        map.insert("part_1".into(), optional_string(value.part_1.as_ref()));
+        map.insert("part_2".into(), optional_string(value.part_2.as_ref()));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for PuzzleMeta {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected puzzle meta to be a JSON object.")?;
+
+        let day = json
+            .get("day")
+            .and_then(|v| v.get::<String>())
+            .and_then(|day| Day::from_str(day).ok())
+            .ok_or("Expected meta.day to be a Day struct.")?;
+
+        let get_optional_string = |key: &str| {
+            json.get(key)
+                .map_or(Some(None), |v| {
+                    if v.is_null() {
+                        Some(None)
+                    } else {
+                        v.get::<String>().map(|s| Some(s.clone()))
+                    }
+                })
+                .ok_or(format!("Expected meta.{key} to be null or string."))
+        };
+
+        let part_two = json
+            .get("part_two")
+            .map_or(Some(false), |v| v.get::<bool>().copied())
+            .ok_or("Expected meta.part_two to be a boolean.")?;
+
+        Ok(PuzzleMeta {
+            day,
+            title: get_optional_string("title")?,
+            part_two,
+            part_1: get_optional_string("part_1")?,
+            part_2: get_optional_string("part_2")?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{PuzzleIndex, PuzzleMeta};
+    use crate::day;
+
+    const PUZZLE: &str = r"\--- Day 1: Historian Hysteria ---
+----------
+
+The *Chief Historian* is always present for the big Christmas sleigh launch.
+
+Your puzzle answer was `2430334`.
+
+\--- Part Two ---
+----------
+
+Your actual left list contains a lot of location IDs.
+
+Your puzzle answer was `28786472`.
+
+Both parts of this puzzle are complete! They provide two gold stars: \*\*
+";
+
+    #[test]
+    fn parses_complete_puzzle() {
+        let meta = PuzzleMeta::from_puzzle(day!(1), PUZZLE);
+        assert_eq!(meta.title, Some("Historian Hysteria".into()));
+        assert!(meta.part_two);
+        assert_eq!(meta.answer(1), Some("2430334"));
+        assert_eq!(meta.answer(2), Some("28786472"));
+        assert_eq!(meta.stars(), 2);
+        assert_eq!(meta.heading(), "Day 01: Historian Hysteria");
+    }
+
+    #[test]
+    fn parses_unsolved_puzzle() {
+        let puzzle = "## \\--- Day 12: Garden Groups ---\n\nWhy not search for the Chief Historian near the gardener?";
+        let meta = PuzzleMeta::from_puzzle(day!(12), puzzle);
+        assert_eq!(meta.title, Some("Garden Groups".into()));
+        assert!(!meta.part_two);
+        assert_eq!(meta.stars(), 0);
+    }
+
+    #[test]
+    fn upsert_keeps_known_fields() {
+        let mut index = PuzzleIndex::default();
+        index.upsert(PuzzleMeta::from_puzzle(day!(1), PUZZLE));
+        index.upsert(PuzzleMeta::new(day!(1)));
+        index.upsert(PuzzleMeta::new(day!(3)));
+        assert_eq!(index.data.len(), 2);
+        assert_eq!(index.heading(day!(1)), "Day 01: Historian Hysteria");
+        assert_eq!(index.heading(day!(2)), "Day 02");
+        assert_eq!(index.get(day!(1)).unwrap().stars(), 2);
+    }
+
+    #[test]
+    fn round_trips_json() {
+        let mut index = PuzzleIndex::default();
+        index.upsert(PuzzleMeta::from_puzzle(day!(1), PUZZLE));
+        index.upsert(PuzzleMeta::new(day!(2)));
+        let json = tinyjson::JsonValue::from(index.clone())
+            .stringify()
+            .unwrap();
+        let parsed = PuzzleIndex::try_from(json).unwrap();
+        assert_eq!(parsed.data, index.data);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_for_malformed_meta() {
+        let json = r#"{ "data": [{ "day": "01", "part_two": "yes" }] }"#.to_string();
+        PuzzleIndex::try_from(json).unwrap();
+    }
+}
@@ -1,13 +1,20 @@
 use std::{env, fs};
 
+pub mod answer;
 pub mod aoc_cli;
+pub mod bench_report;
 pub mod commands;
+#[cfg(feature = "track-memory")]
+pub mod memory;
 pub mod runner;
 
+pub use answer::Answer;
 pub use day::*;
 
 mod day;
+mod meta;
 mod readme_benchmarks;
+mod readme_stars;
 mod run_multi;
 mod timings;
 
@@ -39,19 +46,44 @@ pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+///
+/// Alternative implementations can be registered as named variants, which are selected with
+/// `cargo solve DD --variant <name>` and compared with `cargo time DD --variants`:
+///
+/// ```ignore
+/// advent_of_code::solution!(13, variants {
+///     1: "search" => part_one_search,
+/// });
+/// ```
+///
+/// A day that parses its input with a separate function can register it, so `cargo time` reports the
+/// parse time next to the parts. It comes before the variants, if there are any:
+///
+/// ```ignore
+/// advent_of_code::solution!(2, parse: parse_input);
+/// ```
 #[macro_export]
 macro_rules! solution {
     ($day:expr) => {
-        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]);
+        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]; []; []);
     };
     ($day:expr, 1) => {
-        $crate::solution!(@impl $day, [part_one, 1]);
+        $crate::solution!(@impl $day, [part_one, 1]; []; []);
     };
     ($day:expr, 2) => {
-        $crate::solution!(@impl $day, [part_two, 2]);
+        $crate::solution!(@impl $day, [part_two, 2]; []; []);
+    };
+    ($day:expr, variants { $( $vpart:literal : $name:literal => $vfunc:expr ),* $(,)? }) => {
+        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]; [$( ($vpart, $name, $vfunc) )*]; []);
+    };
+    ($day:expr, parse: $parse:expr) => {
+        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]; []; [$parse]);
+    };
+    ($day:expr, parse: $parse:expr, variants { $( $vpart:literal : $name:literal => $vfunc:expr ),* $(,)? }) => {
+        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]; [$( ($vpart, $name, $vfunc) )*]; [$parse]);
     };
 
-    (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+    (@impl $day:expr, $( [$func:expr, $part:expr] )*; [$( ($vpart:expr, $name:expr, $vfunc:expr) )*]; [$( $parse:expr )?]) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
@@ -59,10 +91,17 @@ macro_rules! solution {
         #[global_allocator]
         static ALLOC: dhat::Alloc = dhat::Alloc;
 
+        #[cfg(all(feature = "track-memory", not(feature = "dhat-heap")))]
+        #[global_allocator]
+        static ALLOC: $crate::template::memory::TrackingAllocator =
+            $crate::template::memory::TrackingAllocator;
+
         fn main() {
             use $crate::template::runner::*;
             let input = $crate::template::read_file("inputs", DAY);
-            $( run_part($func, &input, DAY, $part); )*
+            let variants: Vec<Variant<&str>> = vec![$( Variant::new($vpart, $name, $vfunc), )*];
+            $( run_parse($parse, &input); )?
+            $( run_part_with_variants($func, &variants, &input, DAY, $part); )*
         }
     };
 }
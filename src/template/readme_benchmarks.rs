@@ -2,6 +2,8 @@
 /// The approach taken is similar to how `aoc-readme-stars` handles this.
 use std::{fs, io};
 
+use crate::template::bench_report::{Markdown, Renderer, Report, ReportOptions};
+use crate::template::meta::PuzzleIndex;
 use crate::template::timings::Timings;
 use crate::template::Day;
 
@@ -30,8 +32,9 @@ pub fn get_path_for_bin(day: Day) -> String {
     format!("./src/bin/{day}.rs")
 }
 
-fn locate_table(readme: &str) -> Result<TablePosition, Error> {
-    let matches: Vec<_> = readme.match_indices(MARKER).collect();
+/// Find the section delimited by two occurrences of `marker`, markers included.
+pub(super) fn locate_table(readme: &str, marker: &str) -> Result<TablePosition, Error> {
+    let matches: Vec<_> = readme.match_indices(marker).collect();
 
     if matches.len() > 2 {
         return Err(Error::Parser(
@@ -52,47 +55,50 @@ fn locate_table(readme: &str) -> Result<TablePosition, Error> {
     Ok(TablePosition { pos_start, pos_end })
 }
 
-fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String {
-    let header = format!("{prefix} Benchmarks");
-
-    let mut lines: Vec<String> = vec![
-        MARKER.into(),
-        header,
-        String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
-        "| :---: | :---: | :---:  |".into(),
-    ];
-
-    for timing in timings.data {
-        let path = get_path_for_bin(timing.day);
-        lines.push(format!(
-            "| [Day {}]({}) | `{}` | `{}` |",
-            timing.day.into_inner(),
-            path,
-            timing.part_1.unwrap_or_else(|| "-".into()),
-            timing.part_2.unwrap_or_else(|| "-".into())
-        ));
-    }
+/// Replace the section delimited by `marker` with `table`, which is expected to include the markers.
+pub(super) fn replace_table(s: &mut String, marker: &str, table: &str) -> Result<(), Error> {
+    let positions = locate_table(s, marker)?;
+    s.replace_range(positions.pos_start..positions.pos_end, table);
+    Ok(())
+}
+
+fn construct_table(
+    prefix: &str,
+    timings: &Timings,
+    index: &PuzzleIndex,
+    options: &ReportOptions,
+    total_millis: f64,
+) -> String {
+    let report = Report {
+        total_millis,
+        ..Report::new(timings, index, options)
+    };
 
-    lines.push(String::new());
-    lines.push(format!("**Total: {total_millis:.2}ms**"));
-    lines.push(MARKER.into());
+    let table = Markdown {
+        prefix: prefix.into(),
+    }
+    .render(&report);
 
-    lines.join("\n")
+    [MARKER, &table, MARKER].join("\n")
 }
 
-fn update_content(s: &mut String, timings: Timings, total_millis: f64) -> Result<(), Error> {
-    let positions = locate_table(s)?;
-    let table = construct_table("##", timings, total_millis);
-    s.replace_range(positions.pos_start..positions.pos_end, &table);
-    Ok(())
+fn update_content(
+    s: &mut String,
+    timings: &Timings,
+    index: &PuzzleIndex,
+    options: &ReportOptions,
+    total_millis: f64,
+) -> Result<(), Error> {
+    let table = construct_table("##", timings, index, options, total_millis);
+    replace_table(s, MARKER, &table)
 }
 
-pub fn update(timings: Timings) -> Result<(), Error> {
+pub fn update(timings: &Timings, options: &ReportOptions) -> Result<(), Error> {
     let path = "README.md";
     let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
     let total_millis = timings.total_millis();
-    update_content(&mut readme, timings, total_millis)?;
+    let index = PuzzleIndex::read_from_file();
+    update_content(&mut readme, timings, &index, options, total_millis)?;
     fs::write(path, &readme)?;
     Ok(())
 }
@@ -100,27 +106,36 @@ pub fn update(timings: Timings) -> Result<(), Error> {
 #[cfg(feature = "test_lib")]
 mod tests {
     use super::{update_content, MARKER};
-    use crate::{day, template::timings::Timing, template::timings::Timings};
+    use crate::{
+        day,
+        template::bench_report::ReportOptions,
+        template::meta::{PuzzleIndex, PuzzleMeta},
+        template::timings::Timings,
+        template::timings::{PartTiming, Timing},
+    };
 
     fn get_mock_timings() -> Timings {
         Timings {
             data: vec![
                 Timing {
                     day: day!(1),
-                    part_1: Some("10ms".into()),
-                    part_2: Some("20ms".into()),
+                    parse: None,
+                    part_1: Some(PartTiming::from_nanos(1e+7)),
+                    part_2: Some(PartTiming::from_nanos(2e+7)),
                     total_nanos: 3e+10,
                 },
                 Timing {
                     day: day!(2),
-                    part_1: Some("30ms".into()),
-                    part_2: Some("40ms".into()),
+                    parse: None,
+                    part_1: Some(PartTiming::from_nanos(3e+7)),
+                    part_2: Some(PartTiming::from_nanos(4e+7)),
                     total_nanos: 7e+10,
                 },
                 Timing {
                     day: day!(4),
-                    part_1: Some("40ms".into()),
-                    part_2: Some("50ms".into()),
+                    parse: None,
+                    part_1: Some(PartTiming::from_nanos(4e+7)),
+                    part_2: Some(PartTiming::from_nanos(5e+7)),
                     total_nanos: 9e+10,
                 },
             ],
@@ -131,28 +146,63 @@ mod tests {
     #[should_panic]
     fn errors_if_marker_not_present() {
         let mut s = "# readme".to_string();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(
+            &mut s,
+            &get_mock_timings(),
+            &PuzzleIndex::default(),
+            &ReportOptions::default(),
+            190.0,
+        )
+        .unwrap();
     }
 
     #[test]
     #[should_panic]
     fn errors_if_too_many_markers_present() {
         let mut s = format!("{} {} {}", MARKER, MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(
+            &mut s,
+            &get_mock_timings(),
+            &PuzzleIndex::default(),
+            &ReportOptions::default(),
+            190.0,
+        )
+        .unwrap();
     }
 
     #[test]
     fn updates_empty_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(
+            &mut s,
+            &get_mock_timings(),
+            &PuzzleIndex::default(),
+            &ReportOptions::default(),
+            190.0,
+        )
+        .unwrap();
         assert_eq!(s.contains("## Benchmarks"), true);
     }
 
     #[test]
     fn updates_existing_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(
+            &mut s,
+            &get_mock_timings(),
+            &PuzzleIndex::default(),
+            &ReportOptions::default(),
+            190.0,
+        )
+        .unwrap();
+        update_content(
+            &mut s,
+            &get_mock_timings(),
+            &PuzzleIndex::default(),
+            &ReportOptions::default(),
+            190.0,
+        )
+        .unwrap();
         assert_eq!(s.matches(MARKER).collect::<Vec<&str>>().len(), 2);
         assert_eq!(s.matches("## Benchmarks").collect::<Vec<&str>>().len(), 1);
     }
@@ -160,7 +210,14 @@ mod tests {
     #[test]
     fn format_benchmarks() {
         let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(
+            &mut s,
+            &get_mock_timings(),
+            &PuzzleIndex::default(),
+            &ReportOptions::default(),
+            190.0,
+        )
+        .unwrap();
         let expected = [
             "foo",
             "bar",
@@ -168,10 +225,10 @@ mod tests {
             "## Benchmarks",
             "",
             "| Day | Part 1 | Part 2 |",
-            "| :---: | :---: | :---:  |",
-            "| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` |",
-            "| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` |",
-            "| [Day 4](./src/bin/04.rs) | `40ms` | `50ms` |",
+            "| :---: | :---: | :---: |",
+            "| [Day 1](./src/bin/01.rs) | `10.0ms` | `20.0ms` |",
+            "| [Day 2](./src/bin/02.rs) | `30.0ms` | `40.0ms` |",
+            "| [Day 4](./src/bin/04.rs) | `40.0ms` | `50.0ms` |",
             "",
             "**Total: 190.00ms**",
             "<!--- benchmarking table --->",
@@ -180,4 +237,26 @@ mod tests {
         .join("\n");
         assert_eq!(s, expected);
     }
+
+    #[test]
+    fn format_benchmarks_with_titles() {
+        let mut index = PuzzleIndex::default();
+        index.upsert(PuzzleMeta {
+            title: Some("Historian Hysteria".into()),
+            ..PuzzleMeta::new(day!(1))
+        });
+        let mut s = format!("{}\n{}", MARKER, MARKER);
+        update_content(
+            &mut s,
+            &get_mock_timings(),
+            &index,
+            &ReportOptions::default(),
+            190.0,
+        )
+        .unwrap();
+        assert!(
+            s.contains("| [Day 1: Historian Hysteria](./src/bin/01.rs) | `10.0ms` | `20.0ms` |")
+        );
+        assert!(s.contains("| [Day 2](./src/bin/02.rs) | `30.0ms` | `40.0ms` |"));
+    }
 }
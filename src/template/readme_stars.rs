@@ -0,0 +1,127 @@
+/// Module that updates the readme with the ⭐️ progress recorded in the puzzle index.
+/// The generated table matches the output of `advent-readme-stars`, so both can be used interchangeably.
+use std::fs;
+
+use crate::template::meta::PuzzleIndex;
+use crate::template::readme_benchmarks::{replace_table, Error};
+
+static MARKER: &str = "<!--- advent_readme_stars table --->";
+
+fn star(solved: bool) -> &'static str {
+    if solved {
+        "⭐"
+    } else {
+        " "
+    }
+}
+
+fn construct_table(prefix: &str, year: Option<u16>, index: &PuzzleIndex) -> String {
+    let header = match year {
+        Some(year) => format!("{prefix} {year} Results"),
+        None => format!("{prefix} Results"),
+    };
+
+    let mut lines: Vec<String> = vec![
+        MARKER.into(),
+        header,
+        String::new(),
+        "| Day | Part 1 | Part 2 |".into(),
+        "| :---: | :---: | :---: |".into(),
+    ];
+
+    for meta in index.data.iter().filter(|m| m.stars() > 0) {
+        let day = meta.day.into_inner();
+        let link = match year {
+            Some(year) => format!("https://adventofcode.com/{year}/day/{day}"),
+            None => "https://adventofcode.com".into(),
+        };
+        lines.push(format!(
+            "| [Day {day}]({link}) | {} | {} |",
+            star(meta.part_1.is_some()),
+            star(meta.part_2.is_some())
+        ));
+    }
+
+    lines.push(MARKER.into());
+
+    lines.join("\n")
+}
+
+fn update_content(s: &mut String, year: Option<u16>, index: &PuzzleIndex) -> Result<(), Error> {
+    let table = construct_table("##", year, index);
+    replace_table(s, MARKER, &table)
+}
+
+/// Rewrite the stars table. Returns `false` if there is no progress to write,
+/// in which case the readme is left untouched.
+pub fn update(index: &PuzzleIndex, year: Option<u16>) -> Result<bool, Error> {
+    if index.data.iter().all(|m| m.stars() == 0) {
+        return Ok(false);
+    }
+
+    let path = "README.md";
+    let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
+    update_content(&mut readme, year, index)?;
+    fs::write(path, &readme)?;
+    Ok(true)
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{update_content, MARKER};
+    use crate::{
+        day,
+        template::meta::{PuzzleIndex, PuzzleMeta},
+    };
+
+    fn get_mock_index() -> PuzzleIndex {
+        let mut index = PuzzleIndex::default();
+        index.upsert(PuzzleMeta {
+            part_1: Some("11".into()),
+            part_2: Some("31".into()),
+            ..PuzzleMeta::new(day!(1))
+        });
+        index.upsert(PuzzleMeta {
+            part_1: Some("2".into()),
+            ..PuzzleMeta::new(day!(2))
+        });
+        index.upsert(PuzzleMeta::new(day!(3)));
+        index
+    }
+
+    #[test]
+    #[should_panic]
+    fn errors_if_marker_not_present() {
+        let mut s = "# readme".to_string();
+        update_content(&mut s, Some(2024), &get_mock_index()).unwrap();
+    }
+
+    #[test]
+    fn updates_existing_stars() {
+        let mut s = format!("foo\n{}\n{}\nbar", MARKER, MARKER);
+        update_content(&mut s, Some(2024), &get_mock_index()).unwrap();
+        update_content(&mut s, Some(2024), &get_mock_index()).unwrap();
+        assert_eq!(s.matches(MARKER).count(), 2);
+        assert_eq!(s.matches("## 2024 Results").count(), 1);
+    }
+
+    #[test]
+    fn format_stars() {
+        let mut s = format!("foo\n{}\n{}\nbar", MARKER, MARKER);
+        update_content(&mut s, Some(2024), &get_mock_index()).unwrap();
+        let expected = [
+            "foo",
+            "<!--- advent_readme_stars table --->",
+            "## 2024 Results",
+            "",
+            "| Day | Part 1 | Part 2 |",
+            "| :---: | :---: | :---: |",
+            "| [Day 1](https://adventofcode.com/2024/day/1) | ⭐ | ⭐ |",
+            "| [Day 2](https://adventofcode.com/2024/day/2) | ⭐ |   |",
+            "<!--- advent_readme_stars table --->",
+            "bar",
+        ]
+        .join("\n");
+        assert_eq!(s, expected);
+    }
+}
@@ -4,13 +4,22 @@ use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
 
 use super::{
     all_days,
+    meta::PuzzleIndex,
     timings::{Timing, Timings},
 };
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
+pub fn run_multi(
+    days_to_run: &HashSet<Day>,
+    is_release: bool,
+    is_checked: bool,
+    is_timed: bool,
+    track_memory: bool,
+    all_variants: bool,
+) -> Option<Timings> {
     let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
 
     let mut need_space = false;
+    let index = PuzzleIndex::read_from_file();
 
     // NOTE: use non-duplicate, sorted day values.
     all_days()
@@ -21,10 +30,19 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
             }
             need_space = true;
 
-            println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
-            println!("------");
+            let heading = index.heading(day);
+            println!("{ANSI_BOLD}{heading}{ANSI_RESET}");
+            println!("{}", "-".repeat(heading.chars().count()));
 
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+            let output = child_commands::run_solution(
+                day,
+                is_timed,
+                is_release,
+                is_checked,
+                track_memory,
+                all_variants,
+            )
+            .unwrap();
 
             if output.is_empty() {
                 println!("Not solved.");
@@ -68,7 +86,10 @@ pub fn get_path_for_bin(day: Day) -> String {
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 pub mod child_commands {
     use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+    use crate::template::{
+        timings::{parse_nanos, PartTiming},
+        Day,
+    };
     use std::{
         io::{BufRead, BufReader},
         path::Path,
@@ -77,7 +98,14 @@ pub mod child_commands {
     };
 
     /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    pub fn run_solution(
+        day: Day,
+        is_timed: bool,
+        is_release: bool,
+        is_checked: bool,
+        track_memory: bool,
+        all_variants: bool,
+    ) -> Result<Vec<String>, Error> {
         // skip command invocation for days that have not been scaffolded yet.
         if !Path::new(&get_path_for_bin(day)).exists() {
             return Ok(vec![]);
@@ -86,16 +114,32 @@ pub mod child_commands {
         let day_padded = day.to_string();
         let mut args = vec!["run", "--quiet", "--bin", &day_padded];
 
-        if is_release {
+        if is_checked {
+            // release build with overflow checks, see `[profile.checked]`.
+            args.push("--profile");
+            args.push("checked");
+        } else if is_release {
             args.push("--release");
         }
 
+        if track_memory {
+            args.push("--features");
+            args.push("track-memory");
+        }
+
+        if is_timed || all_variants {
+            args.push("--");
+        }
+
         if is_timed {
             // mirror `--time` flag to child invocations.
-            args.push("--");
             args.push("--time");
         }
 
+        if all_variants {
+            args.push("--variants");
+        }
+
         // spawn child command with piped stdout/stderr.
         // forward output to stdout/stderr while grabbing stdout lines.
 
@@ -128,9 +172,13 @@ pub mod child_commands {
         Ok(output)
     }
 
+    /// Parses the benched lines of a solution run. When all variants were run, each part keeps the
+    /// timing of its fastest variant, e.g. `Part 1 [search]: 42 (74.1ns @ 100 samples)`.
+    /// The parser is benched on its own line, e.g. `Parse: (12.0µs @ 100 samples)`.
     pub fn parse_exec_time(output: &[String], day: Day) -> super::Timing {
         let mut timings = super::Timing {
             day,
+            parse: None,
             part_1: None,
             part_2: None,
             total_nanos: 0_f64,
@@ -143,50 +191,66 @@ pub mod child_commands {
                     return None;
                 }
 
-                let Some((timing_str, nanos)) = parse_time(l) else {
+                let Some(mut timing) = parse_part_timing(l) else {
                     eprintln!("Could not parse timings from line: {l}");
                     return None;
                 };
 
-                let part = l.split(':').next()?;
-                Some((part, timing_str, nanos))
+                let label = l.split(':').next()?;
+                timing.variant = label
+                    .split_once('[')
+                    .and_then(|(_, name)| name.split_once(']'))
+                    .map(|(name, _)| name.to_string());
+                Some((label, timing))
             })
-            .for_each(|(part, timing_str, nanos)| {
-                if part.contains("Part 1") {
-                    timings.part_1 = Some(timing_str.into());
-                } else if part.contains("Part 2") {
-                    timings.part_2 = Some(timing_str.into());
-                }
+            .for_each(|(label, timing)| {
+                let slot = if label == "Parse" {
+                    &mut timings.parse
+                } else if label.contains("Part 1") {
+                    &mut timings.part_1
+                } else if label.contains("Part 2") {
+                    &mut timings.part_2
+                } else {
+                    return;
+                };
 
-                timings.total_nanos += nanos;
+                if slot.as_ref().is_none_or(|t| timing.nanos < t.nanos) {
+                    *slot = Some(timing);
+                }
             });
 
+        timings.total_nanos = [&timings.part_1, &timings.part_2]
+            .into_iter()
+            .flatten()
+            .map(|t| t.nanos)
+            .sum();
+
         timings
     }
 
-    fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
-        s.split(postfix).next()?.parse().ok()
-    }
+    /// Parses a benched line, e.g. `Part 1: 42 (74.1ns @ 100 samples) [median 70.0ns ± 2.0ns] [peak 64 bytes]`.
+    fn parse_part_timing(line: &str) -> Option<PartTiming> {
+        let (head, tail) = line.rsplit_once(" samples)")?;
+        let (time, samples) = head.rsplit_once('(')?.1.split_once('@')?;
+
+        let mut timing = PartTiming {
+            nanos: parse_nanos(time.trim())?,
+            samples: samples.trim().parse().ok(),
+            ..PartTiming::default()
+        };
 
-    fn parse_time(line: &str) -> Option<(&str, f64)> {
-        // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
-        let str_timing = line
-            .split(" samples)")
-            .next()?
-            .split('(')
-            .last()?
-            .split('@')
-            .next()?
-            .trim();
-
-        let parsed_timing = match str_timing {
-            s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
-            s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
-            s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
-            s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
-        }?;
-
-        Some((str_timing, parsed_timing))
+        for section in tail.split('[').skip(1) {
+            let section = section.split(']').next()?.trim();
+            if let Some(median) = section.strip_prefix("median ") {
+                let (median, spread) = median.split_once('±')?;
+                timing.median_nanos = parse_nanos(median.trim());
+                timing.spread_nanos = parse_nanos(spread.trim());
+            } else if let Some(peak) = section.strip_prefix("peak ") {
+                timing.memory = peak.strip_suffix(" bytes")?.parse().ok();
+            }
+        }
+
+        Some(timing)
     }
 
     /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
@@ -220,8 +284,8 @@ pub mod child_commands {
                 day!(1),
             );
             assert_approx_eq!(res.total_nanos, 74130074.13_f64);
-            assert_eq!(res.part_1.unwrap(), "74.13ns");
-            assert_eq!(res.part_2.unwrap(), "74.13ms");
+            assert_approx_eq!(res.part_1.unwrap().nanos, 74.13_f64);
+            assert_approx_eq!(res.part_2.unwrap().nanos, 74130000_f64);
         }
 
         #[test]
@@ -235,8 +299,62 @@ pub mod child_commands {
                 day!(1),
             );
             assert_approx_eq!(res.total_nanos, 2100000000_f64);
-            assert_eq!(res.part_1.unwrap(), "2s");
-            assert_eq!(res.part_2.unwrap(), "100ms");
+            assert_approx_eq!(res.part_1.unwrap().nanos, 2000000000_f64);
+            assert_approx_eq!(res.part_2.unwrap().nanos, 100000000_f64);
+        }
+
+        #[test]
+        fn parses_stats() {
+            let res = parse_exec_time(
+                &[
+                    "Part 1: 0 (74.13ns @ 100000 samples) [median 70.0ns ± 2.5ns] [peak 2048 bytes]".into(),
+                    "Part 2: 10 (74.13ms @ 99999 samples)".into(),
+                ],
+                day!(1),
+            );
+            let part_1 = res.part_1.unwrap();
+            assert_eq!(part_1.samples, Some(100000));
+            assert_eq!(part_1.median_nanos, Some(70_f64));
+            assert_eq!(part_1.spread_nanos, Some(2.5_f64));
+            assert_eq!(part_1.memory, Some(2048));
+            let part_2 = res.part_2.unwrap();
+            assert_eq!(part_2.samples, Some(99999));
+            assert_eq!(part_2.median_nanos, None);
+        }
+
+        #[test]
+        fn parses_parse_time() {
+            let res = parse_exec_time(
+                &[
+                    "Parse: (12.0µs @ 100 samples) [median 11.0µs ± 1.0µs]".into(),
+                    "Part 1: 0 (74.13ns @ 100000 samples)".into(),
+                    "Part 2: 10 (74.13ms @ 99999 samples)".into(),
+                ],
+                day!(1),
+            );
+            let parse = res.parse.unwrap();
+            assert_approx_eq!(parse.nanos, 12000_f64);
+            assert_eq!(parse.median_nanos, Some(11000_f64));
+            // the parts parse their input themselves, so the total stays the sum of the parts.
+            assert_approx_eq!(res.total_nanos, 74130074.13_f64);
+        }
+
+        #[test]
+        fn keeps_fastest_variant() {
+            let res = parse_exec_time(
+                &[
+                    "Part 1 [default]: 480 (2.0ms @ 10 samples)".into(),
+                    "Part 1 [search]: 480 (1.0µs @ 100 samples)".into(),
+                    "Part 2 [default]: 875 (3.0µs @ 100 samples)".into(),
+                    "Part 2 [math]: 875 (4.0µs @ 100 samples)".into(),
+                ],
+                day!(13),
+            );
+            let part_1 = res.part_1.unwrap();
+            assert_eq!(part_1.variant.as_deref(), Some("search"));
+            assert_approx_eq!(part_1.nanos, 1000_f64);
+            assert_eq!(res.part_2.unwrap().variant.as_deref(), Some("default"));
+            assert_approx_eq!(res.total_nanos, 4000_f64);
         }
 
         #[test]
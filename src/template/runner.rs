@@ -1,5 +1,4 @@
 /// Encapsulates code that interacts with solution functions.
-use std::fmt::Display;
 use std::hint::black_box;
 use std::io::{stdout, Write};
 use std::process::Output;
@@ -7,29 +6,176 @@ use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
 use crate::template::ANSI_BOLD;
-use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{aoc_cli, meta, Answer, Day, ANSI_ITALIC, ANSI_RESET};
 
-pub fn run_part<I: Copy, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
-    let part_str = format!("Part {part}");
+/// A named alternative implementation of a part, registered with the `solution!` macro.
+pub struct Variant<I> {
+    pub part: u8,
+    pub name: &'static str,
+    func: Box<dyn Fn(I) -> Option<Answer>>,
+}
+
+impl<I> Variant<I> {
+    pub fn new<T: Into<Answer>>(
+        part: u8,
+        name: &'static str,
+        func: impl Fn(I) -> Option<T> + 'static,
+    ) -> Self {
+        Self {
+            part,
+            name,
+            func: Box::new(move |input| func(input).map(Into::into)),
+        }
+    }
+}
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+/// Name used for the `part_one` / `part_two` functions when listing variants.
+pub const DEFAULT_VARIANT: &str = "default";
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+pub fn run_part<I: Copy, T: Into<Answer>>(
+    func: impl Fn(I) -> Option<T>,
+    input: I,
+    day: Day,
+    part: u8,
+) {
+    run_labelled(func, input, day, part, &format!("Part {part}"));
+}
+
+/// Runs a part, honoring `--variant <name>` to pick an alternative implementation and
+/// `--variants` to run all implementations of the part and compare their answers.
+pub fn run_part_with_variants<I: Copy, T: Into<Answer>>(
+    func: impl Fn(I) -> Option<T>,
+    variants: &[Variant<I>],
+    input: I,
+    day: Day,
+    part: u8,
+) {
+    let args: Vec<String> = env::args().collect();
+
+    if args.iter().any(|a| a == "--variants") {
+        run_all_variants(func, variants, input, part);
+        return;
+    }
+
+    let Some(name) = selected_variant(&args) else {
+        run_part(func, input, day, part);
+        return;
+    };
+
+    if name != DEFAULT_VARIANT && !variants.iter().any(|v| v.name == name) {
+        let mut names: Vec<&str> = variants.iter().map(|v| v.name).collect();
+        names.dedup();
+        eprintln!(
+            "Unknown variant `{name}`, expecting one of {DEFAULT_VARIANT}, {}",
+            names.join(", ")
+        );
+        process::exit(1);
+    }
+
+    // parts without a variant of that name fall back to the default implementation.
+    match variants.iter().find(|v| v.part == part && v.name == name) {
+        Some(variant) => run_labelled(
+            &variant.func,
+            input,
+            day,
+            part,
+            &format!("Part {part} [{name}]"),
+        ),
+        None => run_part(func, input, day, part),
+    }
+}
+
+fn selected_variant(args: &[String]) -> Option<&str> {
+    let index = args.iter().position(|a| a == "--variant")?;
+    args.get(index + 1).map(String::as_str)
+}
+
+fn run_labelled<I: Copy, T: Into<Answer>>(
+    func: impl Fn(I) -> Option<T>,
+    input: I,
+    day: Day,
+    part: u8,
+    label: &str,
+) {
+    let (result, stats) = run_timed(
+        |input| func(input).map(Into::into),
+        input,
+        |result| print_result(result, label, ""),
+    );
+
+    print_result(&result, label, &format_stats(&stats));
 
     if let Some(result) = result {
         submit_result(result, day, part);
     }
 }
 
+/// Runs the parser of a day on its own, so its share of the run time can be reported separately.
+/// The parts parse their input themselves, so the parse time is included in theirs as well.
+pub fn run_parse<T>(func: impl Fn(&str) -> T, input: &str) {
+    let (_, stats) = run_timed(func, input, |_| print!("Parse:"));
+    print!("\r");
+    println!("Parse:{}", format_stats(&stats));
+}
+
+/// Runs the default implementation and every variant of a part on the same input.
+/// Answers are never submitted in this mode, disagreeing answers are reported on stderr.
+fn run_all_variants<I: Copy, T: Into<Answer>>(
+    func: impl Fn(I) -> Option<T>,
+    variants: &[Variant<I>],
+    input: I,
+    part: u8,
+) {
+    let default: Box<dyn Fn(I) -> Option<Answer>> = Box::new(|input| func(input).map(Into::into));
+    let candidates = std::iter::once((DEFAULT_VARIANT, &default)).chain(
+        variants
+            .iter()
+            .filter(|v| v.part == part)
+            .map(|v| (v.name, &v.func)),
+    );
+
+    let mut answers: Vec<(&str, Option<Answer>)> = vec![];
+    for (name, func) in candidates {
+        let label = format!("Part {part} [{name}]");
+        let (result, stats) = run_timed(func, input, |result| print_result(result, &label, ""));
+        print_result(&result, &label, &format_stats(&stats));
+        answers.push((name, result));
+    }
+
+    if answers.windows(2).any(|pair| pair[0].1 != pair[1].1) {
+        let answers: Vec<String> = answers
+            .iter()
+            .map(|(name, answer)| match answer {
+                Some(answer) => format!("{name} = {answer}"),
+                None => format!("{name} = ✖"),
+            })
+            .collect();
+        eprintln!(
+            "{ANSI_BOLD}Variants of part {part} disagree:{ANSI_RESET} {}",
+            answers.join(", ")
+        );
+    }
+}
+
+/// Measurements of a single solution part.
+struct RunStats {
+    average: Duration,
+    median: Duration,
+    spread: Duration,
+    samples: u128,
+    memory: Option<usize>,
+}
+
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
-fn run_timed<I: Copy, T>(
-    func: impl Fn(I) -> T,
-    input: I,
-    hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+fn run_timed<I: Copy, T>(func: impl Fn(I) -> T, input: I, hook: impl Fn(&T)) -> (T, RunStats) {
+    #[cfg(feature = "track-memory")]
+    let baseline = {
+        crate::template::memory::reset_peak();
+        crate::template::memory::current()
+    };
+
     let timer = Instant::now();
     let result = {
         #[cfg(feature = "dhat-heap")]
@@ -39,18 +185,29 @@ fn run_timed<I: Copy, T>(
     };
     let base_time = timer.elapsed();
 
+    #[cfg(feature = "track-memory")]
+    let memory = Some(crate::template::memory::peak_since_reset(baseline));
+    #[cfg(not(feature = "track-memory"))]
+    let memory = None;
+
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
+    let stats = if std::env::args().any(|x| x == "--time") {
         bench(func, input, &base_time)
     } else {
-        (base_time, 1)
+        RunStats {
+            average: base_time,
+            median: base_time,
+            spread: Duration::ZERO,
+            samples: 1,
+            memory: None,
+        }
     };
 
-    (result, run.0, run.1)
+    (result, RunStats { memory, ..stats })
 }
 
-fn bench<I: Copy, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
+fn bench<I: Copy, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> RunStats {
     let mut stdout = stdout();
 
     print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
@@ -67,11 +224,16 @@ fn bench<I: Copy, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (
         timers.push(timer.elapsed());
     }
 
-    (
+    let (median, spread) = median_and_spread(&mut timers);
+
+    RunStats {
         #[allow(clippy::cast_possible_truncation)]
-        Duration::from_nanos(average_duration(&timers) as u64),
-        bench_iterations,
-    )
+        average: Duration::from_nanos(average_duration(&timers) as u64),
+        median,
+        spread,
+        samples: bench_iterations,
+        memory: None,
+    }
 }
 
 fn average_duration(numbers: &[Duration]) -> u128 {
@@ -82,6 +244,17 @@ fn average_duration(numbers: &[Duration]) -> u128 {
         / numbers.len() as u128
 }
 
+/// Median and median absolute deviation of a set of timings.
+fn median_and_spread(timers: &mut [Duration]) -> (Duration, Duration) {
+    timers.sort_unstable();
+    let median = timers[timers.len() / 2];
+
+    let mut deviations: Vec<Duration> = timers.iter().map(|t| t.abs_diff(median)).collect();
+    deviations.sort_unstable();
+
+    (median, deviations[deviations.len() / 2])
+}
+
 fn format_duration(duration: &Duration, samples: u128) -> String {
     if samples == 1 {
         format!(" ({duration:.1?})")
@@ -90,7 +263,26 @@ fn format_duration(duration: &Duration, samples: u128) -> String {
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+/// Formats the measurements of a run. Benched runs append `[median X ± Y]` and,
+/// when tracked, `[peak N bytes]`, both of which are parsed by `cargo time`.
+fn format_stats(stats: &RunStats) -> String {
+    let mut s = format_duration(&stats.average, stats.samples);
+
+    if stats.samples > 1 {
+        s.push_str(&format!(
+            " [median {:.1?} ± {:.1?}]",
+            stats.median, stats.spread
+        ));
+    }
+
+    if let Some(memory) = stats.memory {
+        s.push_str(&format!(" [peak {memory} bytes]"));
+    }
+
+    s
+}
+
+fn print_result(result: &Option<Answer>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
     match result {
@@ -128,8 +320,8 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
 ///  2. aoc-cli is installed.
-fn submit_result<T: Display>(
-    result: T,
+fn submit_result(
+    result: Answer,
     day: Day,
     part: u8,
 ) -> Option<Result<Output, aoc_cli::AocCommandError>> {
@@ -161,5 +353,14 @@ fn submit_result<T: Display>(
     }
 
     println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    let answer = result.to_string();
+    let output = aoc_cli::submit(day, part, &answer);
+
+    if output.as_ref().is_ok_and(aoc_cli::is_correct_answer) {
+        if let Err(e) = meta::record_answer(day, part, &answer) {
+            eprintln!("failed to record answer: {e}");
+        }
+    }
+
+    Some(output)
 }
@@ -1,19 +1,79 @@
-use std::{collections::HashMap, fs, io::Error, str::FromStr};
+use std::{collections::HashMap, fs, io::Error, str::FromStr, time::Duration};
 use tinyjson::JsonValue;
 
 use crate::template::Day;
 
 static TIMINGS_FILE_PATH: &str = "./data/timings.json";
 
+/// Version of the timings file format. Version 1 (no `version` key) stored preformatted strings.
+const TIMINGS_FILE_VERSION: f64 = 2.0;
+
 /// Represents benchmark times for a single day.
 #[derive(Clone, Debug)]
 pub struct Timing {
     pub day: Day,
-    pub part_1: Option<String>,
-    pub part_2: Option<String>,
+    /// Time spent in the parser registered with the `solution!` macro, if the day has one.
+    /// It is already part of the part timings and not counted in the total again.
+    pub parse: Option<PartTiming>,
+    pub part_1: Option<PartTiming>,
+    pub part_2: Option<PartTiming>,
     pub total_nanos: f64,
 }
 
+/// Represents the benchmark measurements of a single part.
+/// Optional values are unknown for timings migrated from older files.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PartTiming {
+    pub nanos: f64,
+    pub samples: Option<u128>,
+    pub median_nanos: Option<f64>,
+    pub spread_nanos: Option<f64>,
+    pub memory: Option<u64>,
+    /// The implementation that was measured, set when benchmarking with `--variants`.
+    pub variant: Option<String>,
+}
+
+impl PartTiming {
+    pub fn from_nanos(nanos: f64) -> Self {
+        Self {
+            nanos,
+            ..Self::default()
+        }
+    }
+}
+
+impl Timing {
+    pub fn part(&self, part: u8) -> Option<&PartTiming> {
+        match part {
+            1 => self.part_1.as_ref(),
+            2 => self.part_2.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+/// Formats nanoseconds the same way the runner prints durations, e.g. `74.0ns` or `1.2ms`.
+pub fn format_nanos(nanos: f64) -> String {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let duration = Duration::from_nanos(nanos.round() as u64);
+    format!("{duration:.1?}")
+}
+
+/// Parses a duration as printed by the runner, e.g. `74.13ns`, into nanoseconds.
+pub fn parse_nanos(s: &str) -> Option<f64> {
+    fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
+        s.split(postfix).next()?.trim().parse().ok()
+    }
+
+    // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
+    match s {
+        s if s.contains("ns") => parse_to_float(s, "ns"),
+        s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
+        s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
+        s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
+    }
+}
+
 /// Represents benchmark times for a set of days.
 /// Can be serialized from / to JSON.
 #[derive(Clone, Debug, Default)]
@@ -30,11 +90,23 @@ impl Timings {
     }
 
     /// Rehydrate timings from a JSON file. If not present, returns empty timings.
+    /// Files written by older versions are upgraded in place.
     pub fn read_from_file() -> Self {
-        fs::read_to_string(TIMINGS_FILE_PATH)
-            .map_err(|x| x.to_string())
-            .and_then(Timings::try_from)
-            .unwrap_or_default()
+        let Ok(contents) = fs::read_to_string(TIMINGS_FILE_PATH) else {
+            return Timings::default();
+        };
+
+        let needs_migration = needs_migration(&contents);
+        let timings = Timings::try_from(contents).unwrap_or_default();
+
+        if needs_migration && !timings.data.is_empty() {
+            match timings.store_file() {
+                Ok(()) => println!("Upgraded \"{TIMINGS_FILE_PATH}\" to the current format."),
+                Err(e) => eprintln!("Failed to upgrade \"{TIMINGS_FILE_PATH}\": {e}"),
+            }
+        }
+
+        timings
     }
 
     /// Merge two sets of timings, overwriting `self` with `other` if present.
@@ -51,7 +123,7 @@ impl Timings {
             }
         }
 
-        data.sort_unstable_by(|a, b| a.day.cmp(&b.day));
+        data.sort_unstable_by_key(|a| a.day);
         Timings { data }
     }
 
@@ -67,12 +139,23 @@ impl Timings {
     }
 }
 
+/// Checks whether a timings document predates the current format.
+fn needs_migration(contents: &str) -> bool {
+    JsonValue::from_str(contents).is_ok_and(|json| {
+        json.get::<HashMap<String, JsonValue>>()
+            .and_then(|map| map.get("version"))
+            .and_then(|v| v.get::<f64>())
+            .is_none_or(|v| *v < TIMINGS_FILE_VERSION)
+    })
+}
+
 /* -------------------------------------------------------------------------- */
 
 impl From<Timings> for JsonValue {
     fn from(value: Timings) -> Self {
         let mut map: HashMap<String, JsonValue> = HashMap::new();
 
+        map.insert("version".into(), JsonValue::Number(TIMINGS_FILE_VERSION));
         map.insert(
             "data".into(),
             JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
@@ -114,23 +197,28 @@ impl From<&Timing> for JsonValue {
         map.insert("day".into(), JsonValue::String(value.day.to_string()));
         map.insert("total_nanos".into(), JsonValue::Number(value.total_nanos));
 
-        let part_1 = value.part_1.clone().map(JsonValue::String);
-        let part_2 = value.part_2.clone().map(JsonValue::String);
+        map.insert(
+            "parse".into(),
+            value
+                .parse
+                .as_ref()
+                .map_or(JsonValue::Null, JsonValue::from),
+        );
 
         map.insert(
             "part_1".into(),
-            match part_1 {
-                Some(x) => x,
-                None => JsonValue::Null,
-            },
+            value
+                .part_1
+                .as_ref()
+                .map_or(JsonValue::Null, JsonValue::from),
         );
 
         map.insert(
             "part_2".into(),
-            match part_2 {
-                Some(x) => x,
-                None => JsonValue::Null,
-            },
+            value
+                .part_2
+                .as_ref()
+                .map_or(JsonValue::Null, JsonValue::from),
         );
 
         JsonValue::Object(map)
@@ -151,25 +239,40 @@ impl TryFrom<&JsonValue> for Timing {
             .and_then(|day| Day::from_str(day).ok())
             .ok_or("Expected timing.day to be a Day struct.")?;
 
-        let part_1 = json
-            .get("part_1")
-            .map(|v| if v.is_null() { None } else { v.get::<String>() })
-            .ok_or("Expected timing.part_1 to be null or string.")?;
-
-        let part_2 = json
-            .get("part_2")
-            .map(|v| if v.is_null() { None } else { v.get::<String>() })
-            .ok_or("Expected timing.part_2 to be null or string.")?;
-
         let total_nanos = json
             .get("total_nanos")
             .and_then(|v| v.get::<f64>().copied())
             .ok_or("Expected timing.total_nanos to be a number.")?;
 
+        let parse = match json.get("parse") {
+            None => None,
+            Some(v) if v.is_null() => None,
+            Some(v) => Some(PartTiming::try_from(v)?),
+        };
+
+        let parse_part = |key: &str| -> Result<Option<PartTiming>, String> {
+            let value = json.get(key).ok_or(format!(
+                "Expected timing.{key} to be null, string or object."
+            ))?;
+
+            if value.is_null() {
+                return Ok(None);
+            }
+
+            // version 1 stored the formatted duration.
+            if let Some(s) = value.get::<String>() {
+                let nanos = parse_nanos(s).ok_or(format!("Could not parse timing.{key} `{s}`."))?;
+                return Ok(Some(PartTiming::from_nanos(nanos)));
+            }
+
+            PartTiming::try_from(value).map(Some)
+        };
+
         Ok(Timing {
             day,
-            part_1: part_1.cloned(),
-            part_2: part_2.cloned(),
+            parse,
+            part_1: parse_part("part_1")?,
+            part_2: parse_part("part_2")?,
             total_nanos,
         })
     }
@@ -177,30 +280,113 @@ impl TryFrom<&JsonValue> for Timing {
 
 /* -------------------------------------------------------------------------- */
 
+fn optional_number(value: Option<f64>) -> JsonValue {
+    value.map_or(JsonValue::Null, JsonValue::Number)
+}
+
+impl From<&PartTiming> for JsonValue {
+    fn from(value: &PartTiming) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert("nanos".into(), JsonValue::Number(value.nanos));
+        #[allow(clippy::cast_precision_loss)]
+        map.insert(
+            "samples".into(),
+            optional_number(value.samples.map(|s| s as f64)),
+        );
+        map.insert("median_nanos".into(), optional_number(value.median_nanos));
+        map.insert("spread_nanos".into(), optional_number(value.spread_nanos));
+        #[allow(clippy::cast_precision_loss)]
+        map.insert(
+            "memory".into(),
+            optional_number(value.memory.map(|m| m as f64)),
+        );
+        map.insert(
+            "variant".into(),
+            value
+                .variant
+                .clone()
+                .map_or(JsonValue::Null, JsonValue::String),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for PartTiming {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected part timing to be a JSON object.")?;
+
+        let nanos = json
+            .get("nanos")
+            .and_then(|v| v.get::<f64>().copied())
+            .ok_or("Expected part.nanos to be a number.")?;
+
+        let get_optional_number = |key: &str| match json.get(key) {
+            None => Ok(None),
+            Some(v) if v.is_null() => Ok(None),
+            Some(v) => v
+                .get::<f64>()
+                .copied()
+                .map(Some)
+                .ok_or(format!("Expected part.{key} to be null or number.")),
+        };
+
+        let variant = match json.get("variant") {
+            None => None,
+            Some(v) if v.is_null() => None,
+            Some(v) => Some(
+                v.get::<String>()
+                    .cloned()
+                    .ok_or("Expected part.variant to be null or string.")?,
+            ),
+        };
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Ok(PartTiming {
+            nanos,
+            samples: get_optional_number("samples")?.map(|s| s as u128),
+            median_nanos: get_optional_number("median_nanos")?,
+            spread_nanos: get_optional_number("spread_nanos")?,
+            memory: get_optional_number("memory")?.map(|m| m as u64),
+            variant,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
 #[cfg(feature = "test_lib")]
 mod tests {
     use crate::day;
 
-    use super::{Timing, Timings};
+    use super::{PartTiming, Timing, Timings};
 
     fn get_mock_timings() -> Timings {
         Timings {
             data: vec![
                 Timing {
                     day: day!(1),
-                    part_1: Some("10ms".into()),
-                    part_2: Some("20ms".into()),
+                    parse: None,
+                    part_1: Some(PartTiming::from_nanos(10_000_000_f64)),
+                    part_2: Some(PartTiming::from_nanos(20_000_000_f64)),
                     total_nanos: 3e+10,
                 },
                 Timing {
                     day: day!(2),
-                    part_1: Some("30ms".into()),
-                    part_2: Some("40ms".into()),
+                    parse: None,
+                    part_1: Some(PartTiming::from_nanos(30_000_000_f64)),
+                    part_2: Some(PartTiming::from_nanos(40_000_000_f64)),
                     total_nanos: 7e+10,
                 },
                 Timing {
                     day: day!(4),
-                    part_1: Some("40ms".into()),
+                    parse: None,
+                    part_1: Some(PartTiming::from_nanos(40_000_000_f64)),
                     part_2: None,
                     total_nanos: 4e+10,
                 },
@@ -209,20 +395,46 @@ mod tests {
     }
 
     mod deserialization {
-        use crate::{day, template::timings::Timings};
+        use crate::{
+            day,
+            template::timings::{needs_migration, PartTiming, Timings},
+        };
 
         #[test]
         fn handles_json_timings() {
-            let json = r#"{ "data": [{ "day": "01", "part_1": "1ms", "part_2": null, "total_nanos": 1000000000 }] }"#.to_string();
+            let json = r#"{ "version": 2, "data": [{ "day": "01", "parse": { "nanos": 2000 }, "part_1": { "nanos": 1000000, "samples": 10, "median_nanos": 900000, "spread_nanos": 100, "memory": null, "variant": "search" }, "part_2": null, "total_nanos": 1000000000 }] }"#.to_string();
+            assert!(!needs_migration(&json));
             let timings = Timings::try_from(json).unwrap();
             assert_eq!(timings.data.len(), 1);
             let timing = timings.data.first().unwrap();
             assert_eq!(timing.day, day!(1));
-            assert_eq!(timing.part_1, Some("1ms".to_string()));
+            assert_eq!(
+                timing.part_1,
+                Some(PartTiming {
+                    nanos: 1_000_000_f64,
+                    samples: Some(10),
+                    median_nanos: Some(900_000_f64),
+                    spread_nanos: Some(100_f64),
+                    memory: None,
+                    variant: Some("search".into()),
+                })
+            );
+            assert_eq!(timing.parse, Some(PartTiming::from_nanos(2000_f64)));
             assert_eq!(timing.part_2, None);
             assert_eq!(timing.total_nanos, 1_000_000_000_f64);
         }
 
+        #[test]
+        fn migrates_formatted_timings() {
+            let json = r#"{ "data": [{ "day": "01", "part_1": "1ms", "part_2": "74.13ns", "total_nanos": 1000074.13 }] }"#.to_string();
+            assert!(needs_migration(&json));
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            assert_eq!(timing.parse, None);
+            assert_eq!(timing.part_1, Some(PartTiming::from_nanos(1_000_000_f64)));
+            assert_eq!(timing.part_2, Some(PartTiming::from_nanos(74.13_f64)));
+        }
+
         #[test]
         fn handles_empty_timings() {
             let json = r#"{ "data": [] }"#.to_string();
@@ -247,9 +459,28 @@ mod tests {
 
     mod serialization {
         use super::get_mock_timings;
+        use crate::template::timings::{format_nanos, Timings};
         use std::collections::HashMap;
         use tinyjson::JsonValue;
 
+        #[test]
+        fn round_trips_timings() {
+            let timings = get_mock_timings();
+            let json = JsonValue::from(timings.clone()).stringify().unwrap();
+            let parsed = Timings::try_from(json).unwrap();
+            assert_eq!(parsed.data.len(), 3);
+            assert_eq!(parsed.data[0].part_1, timings.data[0].part_1);
+            assert_eq!(parsed.data[2].part_2, None);
+        }
+
+        #[test]
+        fn formats_nanos() {
+            assert_eq!(format_nanos(74.13), "74.0ns");
+            assert_eq!(format_nanos(1_500.0), "1.5µs");
+            assert_eq!(format_nanos(20_000_000.0), "20.0ms");
+            assert_eq!(format_nanos(2_100_000_000.0), "2.1s");
+        }
+
         #[test]
         fn serializes_timings() {
             let timings = get_mock_timings();
@@ -271,7 +502,7 @@ mod tests {
     mod is_day_complete {
         use crate::{
             day,
-            template::timings::{Timing, Timings},
+            template::timings::{PartTiming, Timing, Timings},
         };
 
         #[test]
@@ -279,13 +510,14 @@ mod tests {
             let timings = Timings {
                 data: vec![Timing {
                     day: day!(1),
-                    part_1: Some("1ms".into()),
-                    part_2: Some("2ms".into()),
+                    parse: None,
+                    part_1: Some(PartTiming::from_nanos(1_000_000_f64)),
+                    part_2: Some(PartTiming::from_nanos(2_000_000_f64)),
                     total_nanos: 3_000_000_000_f64,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), true);
+            assert_eq!(timings.is_day_complete(day!(1)), true);
         }
 
         #[test]
@@ -293,13 +525,14 @@ mod tests {
             let timings = Timings {
                 data: vec![Timing {
                     day: day!(1),
-                    part_1: Some("1ms".into()),
+                    parse: None,
+                    part_1: Some(PartTiming::from_nanos(1_000_000_f64)),
                     part_2: None,
                     total_nanos: 1_000_000_000_f64,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
 
         #[test]
@@ -307,20 +540,21 @@ mod tests {
             let timings = Timings {
                 data: vec![Timing {
                     day: day!(1),
+                    parse: None,
                     part_1: None,
                     part_2: None,
                     total_nanos: 0.0,
                 }],
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
     }
 
     mod merge {
         use crate::{
             day,
-            template::timings::{Timing, Timings},
+            template::timings::{PartTiming, Timing, Timings},
         };
 
         use super::get_mock_timings;
@@ -331,6 +565,7 @@ mod tests {
             let other = Timings {
                 data: vec![Timing {
                     day: day!(3),
+                    parse: None,
                     part_1: None,
                     part_2: None,
                     total_nanos: 0_f64,
@@ -351,6 +586,7 @@ mod tests {
             let other = Timings {
                 data: vec![Timing {
                     day: day!(2),
+                    parse: None,
                     part_1: None,
                     part_2: None,
                     total_nanos: 0_f64,
@@ -0,0 +1,63 @@
+//! Debug visualisations for solutions that stay out of benchmarks.
+//!
+//! Output is only produced when a solution runs with `--viz` (e.g. `cargo solve 14 --viz`) and never
+//! during `--time` runs. It is written to stderr, so it does not interfere with the runner parsing stdout.
+use std::env;
+use std::sync::OnceLock;
+
+static ENABLED: OnceLock<bool> = OnceLock::new();
+
+/// Something that can be drawn as text, e.g. a grid.
+pub trait Visualize {
+    fn render(&self) -> String;
+}
+
+fn is_enabled(args: &[String]) -> bool {
+    args.iter().any(|a| a == "--viz") && !args.iter().any(|a| a == "--time")
+}
+
+/// Whether visualisations should be shown. Use this to skip expensive preparation of debug output.
+pub fn enabled() -> bool {
+    *ENABLED.get_or_init(|| is_enabled(&env::args().collect::<Vec<_>>()))
+}
+
+/// Shows `value` below a `label` line.
+pub fn show(label: &str, value: &impl Visualize) {
+    if enabled() {
+        eprintln!("{label}\n{}", value.render());
+    }
+}
+
+/// Like `eprintln!`, but only evaluates and prints its arguments when visualisations are enabled.
+#[macro_export]
+macro_rules! viz {
+    ($($arg:tt)*) => {
+        if $crate::viz::enabled() {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::is_enabled;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn enabled_by_flag() {
+        assert!(is_enabled(&args(&["target/debug/14", "--viz"])));
+        assert!(!is_enabled(&args(&["target/debug/14"])));
+    }
+
+    #[test]
+    fn disabled_when_timed() {
+        assert!(!is_enabled(&args(&[
+            "target/release/14",
+            "--viz",
+            "--time"
+        ])));
+    }
+}
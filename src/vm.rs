@@ -0,0 +1,268 @@
+//! A small interpreter framework for the "puzzle CPU" family of puzzles.
+//!
+//! A puzzle provides an [`InstructionSet`] that executes a single decoded instruction against a [`Cpu`],
+//! the [`Machine`] drives the fetch / execute loop and handles breakpoints, step limits and tracing.
+use std::collections::HashSet;
+use std::fmt::Debug;
+
+/// What the machine should do after an instruction was executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    /// Advance the program counter by [`InstructionSet::WIDTH`].
+    Next,
+    /// Continue at the given address.
+    Jump(usize),
+    /// Stop execution.
+    Halt,
+}
+
+/// Why a run returned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The program can continue, returned by [`Machine::step`] only.
+    Running,
+    /// The program counter left the program, or an instruction returned [`Flow::Halt`].
+    Halted,
+    /// Execution reached an address with a breakpoint. The instruction at that address has not run yet.
+    Breakpoint(usize),
+    /// The step limit passed to [`Machine::run_with_limit`] was exhausted.
+    StepLimit,
+}
+
+/// The opcode table of a puzzle CPU.
+pub trait InstructionSet {
+    type Instruction: Clone + Debug;
+    type Word: Copy + Default + Debug;
+
+    /// Number of addresses a single instruction occupies, used by [`Flow::Next`].
+    const WIDTH: usize = 1;
+
+    fn execute(&self, instruction: &Self::Instruction, cpu: &mut Cpu<Self::Word>) -> Flow;
+}
+
+/// Registers, program counter and output buffer of a machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cpu<W> {
+    pub registers: Vec<W>,
+    pub pc: usize,
+    pub output: Vec<W>,
+}
+
+impl<W: Copy + Default> Cpu<W> {
+    #[must_use]
+    pub fn new(registers: usize) -> Self {
+        Self {
+            registers: vec![W::default(); registers],
+            pc: 0,
+            output: vec![],
+        }
+    }
+}
+
+/// A single executed instruction, recorded when tracing is enabled.
+#[derive(Debug, Clone)]
+pub struct TraceEntry<I, W> {
+    pub pc: usize,
+    pub instruction: I,
+    /// Register contents after the instruction ran.
+    pub registers: Vec<W>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Machine<S: InstructionSet> {
+    pub isa: S,
+    pub program: Vec<S::Instruction>,
+    pub cpu: Cpu<S::Word>,
+    steps: usize,
+    breakpoints: HashSet<usize>,
+    trace: Option<Vec<TraceEntry<S::Instruction, S::Word>>>,
+}
+
+impl<S: InstructionSet> Machine<S> {
+    /// Creates a machine with `registers` zeroed registers.
+    #[must_use]
+    pub fn new(isa: S, program: Vec<S::Instruction>, registers: usize) -> Self {
+        Self {
+            isa,
+            program,
+            cpu: Cpu::new(registers),
+            steps: 0,
+            breakpoints: HashSet::new(),
+            trace: None,
+        }
+    }
+
+    /// Resets program counter, output and step count while keeping the program and breakpoints.
+    pub fn reset(&mut self, registers: Vec<S::Word>) {
+        self.cpu = Cpu {
+            registers,
+            pc: 0,
+            output: vec![],
+        };
+        self.steps = 0;
+        if let Some(trace) = self.trace.as_mut() {
+            trace.clear();
+        }
+    }
+
+    /// Total number of instructions executed.
+    #[must_use]
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn add_breakpoint(&mut self, address: usize) {
+        self.breakpoints.insert(address);
+    }
+
+    pub fn remove_breakpoint(&mut self, address: usize) {
+        self.breakpoints.remove(&address);
+    }
+
+    /// Starts recording every executed instruction.
+    pub fn enable_trace(&mut self) {
+        self.trace.get_or_insert_with(Vec::new);
+    }
+
+    #[must_use]
+    pub fn trace(&self) -> &[TraceEntry<S::Instruction, S::Word>] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    /// Executes the instruction at the program counter.
+    pub fn step(&mut self) -> Status {
+        let Some(instruction) = self.program.get(self.cpu.pc) else {
+            return Status::Halted;
+        };
+
+        let pc = self.cpu.pc;
+        let flow = self.isa.execute(instruction, &mut self.cpu);
+        self.steps += 1;
+
+        if let Some(trace) = self.trace.as_mut() {
+            trace.push(TraceEntry {
+                pc,
+                instruction: instruction.clone(),
+                registers: self.cpu.registers.clone(),
+            });
+        }
+
+        match flow {
+            Flow::Next => self.cpu.pc += S::WIDTH,
+            Flow::Jump(address) => self.cpu.pc = address,
+            Flow::Halt => return Status::Halted,
+        }
+
+        if self.cpu.pc < self.program.len() {
+            Status::Running
+        } else {
+            Status::Halted
+        }
+    }
+
+    /// Runs until the program halts or reaches a breakpoint.
+    /// Calling `run` again after a breakpoint resumes execution from there.
+    pub fn run(&mut self) -> Status {
+        self.run_with_limit(usize::MAX)
+    }
+
+    /// Like [`Machine::run`], but gives up after `max_steps` instructions.
+    pub fn run_with_limit(&mut self, max_steps: usize) -> Status {
+        for _ in 0..max_steps {
+            match self.step() {
+                Status::Running if self.breakpoints.contains(&self.cpu.pc) => {
+                    return Status::Breakpoint(self.cpu.pc);
+                }
+                Status::Running => {}
+                status => return status,
+            }
+        }
+
+        if self.cpu.pc < self.program.len() {
+            Status::StepLimit
+        } else {
+            Status::Halted
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Cpu, Flow, InstructionSet, Machine, Status};
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Inc(usize),
+        Out(usize),
+        /// Jump to the address if the register is below the value.
+        JumpLess(usize, i64, usize),
+        Halt,
+    }
+
+    struct Counter;
+
+    impl InstructionSet for Counter {
+        type Instruction = Op;
+        type Word = i64;
+
+        fn execute(&self, instruction: &Op, cpu: &mut Cpu<i64>) -> Flow {
+            match *instruction {
+                Op::Inc(r) => cpu.registers[r] += 1,
+                Op::Out(r) => cpu.output.push(cpu.registers[r]),
+                Op::JumpLess(r, value, address) if cpu.registers[r] < value => {
+                    return Flow::Jump(address)
+                }
+                Op::JumpLess(..) => {}
+                Op::Halt => return Flow::Halt,
+            }
+            Flow::Next
+        }
+    }
+
+    fn counter(limit: i64) -> Machine<Counter> {
+        let program = vec![
+            Op::Inc(0),
+            Op::Out(0),
+            Op::JumpLess(0, limit, 0),
+            Op::Halt,
+            Op::Out(0),
+        ];
+        Machine::new(Counter, program, 1)
+    }
+
+    #[test]
+    fn runs_until_halt() {
+        let mut machine = counter(3);
+        assert_eq!(machine.run(), Status::Halted);
+        assert_eq!(machine.cpu.output, vec![1, 2, 3]);
+        assert_eq!(machine.steps(), 10);
+    }
+
+    #[test]
+    fn stops_at_step_limit() {
+        let mut machine = counter(i64::MAX);
+        assert_eq!(machine.run_with_limit(100), Status::StepLimit);
+        assert_eq!(machine.steps(), 100);
+    }
+
+    #[test]
+    fn stops_at_breakpoints_and_resumes() {
+        let mut machine = counter(3);
+        machine.add_breakpoint(1);
+        machine.enable_trace();
+
+        assert_eq!(machine.run(), Status::Breakpoint(1));
+        assert!(machine.cpu.output.is_empty());
+        assert_eq!(machine.run(), Status::Breakpoint(1));
+        assert_eq!(machine.cpu.output, vec![1]);
+
+        machine.remove_breakpoint(1);
+        assert_eq!(machine.run(), Status::Halted);
+        assert_eq!(machine.trace().len(), 10);
+        assert_eq!(machine.trace()[0].registers, vec![1]);
+
+        machine.reset(vec![0]);
+        assert_eq!(machine.run(), Status::Halted);
+        assert_eq!(machine.cpu.output, vec![1, 2, 3]);
+    }
+}
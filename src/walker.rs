@@ -0,0 +1,202 @@
+//! Simulates a walker that moves straight ahead on a grid and turns in front of obstacles.
+//!
+//! [`Walker`] precomputes, for every cell and direction, where the walker stops in front of the next
+//! obstacle. Loop checks then jump from turn to turn instead of stepping cell by cell, and a single
+//! temporary obstacle can be added without rebuilding the tables.
+use std::collections::HashSet;
+
+use crate::grid::{Direction, Grid, Pos};
+
+/// The result of a step-by-step walk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Walk {
+    /// Every position the walker stood on with its facing, starting with the start position.
+    /// Turning in place adds an entry with the new facing.
+    pub path: Vec<(Pos, Direction)>,
+    /// Whether the walk ended in a cycle instead of leaving the grid.
+    pub looped: bool,
+}
+
+impl Walk {
+    /// Distinct positions in the order they were first visited.
+    #[must_use]
+    pub fn visited(&self) -> Vec<Pos> {
+        let mut seen = HashSet::new();
+        self.path
+            .iter()
+            .map(|&(pos, _)| pos)
+            .filter(|&pos| seen.insert(pos))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Walker {
+    obstacles: Grid<bool>,
+    turn: fn(Direction) -> Direction,
+    /// Per direction the last free position before the next obstacle, `None` if the walker leaves the grid.
+    jumps: [Grid<Option<Pos>>; 4],
+}
+
+impl Walker {
+    /// Creates a walker for a grid of obstacles, `turn` picks the new facing in front of an obstacle.
+    #[must_use]
+    pub fn new(obstacles: Grid<bool>, turn: fn(Direction) -> Direction) -> Self {
+        let jumps = Direction::ALL.map(|direction| Self::jump_table(&obstacles, direction));
+        Self {
+            obstacles,
+            turn,
+            jumps,
+        }
+    }
+
+    fn jump_table(obstacles: &Grid<bool>, direction: Direction) -> Grid<Option<Pos>> {
+        let mut jumps = Grid::new(obstacles.width, obstacles.height, None);
+        // visit cells nearest to the edge the walker is heading to first, so the next cell is already known.
+        let mut positions: Vec<Pos> = obstacles.positions().collect();
+        if matches!(direction, Direction::Right | Direction::Down) {
+            positions.reverse();
+        }
+        for pos in positions {
+            jumps[pos] = match obstacles.step(pos, direction) {
+                None => None,
+                Some(next) if obstacles[next] => Some(pos),
+                Some(next) => jumps[next],
+            };
+        }
+        jumps
+    }
+
+    #[must_use]
+    pub fn obstacles(&self) -> &Grid<bool> {
+        &self.obstacles
+    }
+
+    /// Walks cell by cell from `start` until the walker leaves the grid or repeats a state.
+    #[must_use]
+    pub fn walk(&self, start: Pos, facing: Direction) -> Walk {
+        let mut seen = Grid::new(self.obstacles.width, self.obstacles.height, 0_u8);
+        let (mut pos, mut facing) = (start, facing);
+        let mut path = vec![];
+
+        loop {
+            let bit = 1 << facing as u8;
+            if seen[pos] & bit != 0 {
+                return Walk { path, looped: true };
+            }
+            seen[pos] |= bit;
+            path.push((pos, facing));
+
+            match self.obstacles.step(pos, facing) {
+                None => {
+                    return Walk {
+                        path,
+                        looped: false,
+                    }
+                }
+                Some(next) if self.obstacles[next] => facing = (self.turn)(facing),
+                Some(next) => pos = next,
+            }
+        }
+    }
+
+    /// Where the walker stops when heading in `direction` from `pos`, taking the `extra` obstacle into
+    /// account. `None` if it leaves the grid.
+    fn jump(&self, pos: Pos, direction: Direction, extra: Option<Pos>) -> Option<Pos> {
+        let target = self.jumps[direction as usize][pos];
+        let Some(extra) = extra else {
+            return target;
+        };
+
+        let distance = |(x, y): Pos| x.abs_diff(pos.0) + y.abs_diff(pos.1);
+        let ahead = match direction {
+            Direction::Up => extra.0 == pos.0 && extra.1 < pos.1,
+            Direction::Down => extra.0 == pos.0 && extra.1 > pos.1,
+            Direction::Left => extra.1 == pos.1 && extra.0 < pos.0,
+            Direction::Right => extra.1 == pos.1 && extra.0 > pos.0,
+        };
+        if ahead && target.is_none_or(|target| distance(extra) <= distance(target)) {
+            // stop on the cell right before the extra obstacle.
+            self.obstacles.step(extra, direction.opposite())
+        } else {
+            target
+        }
+    }
+
+    /// Whether walking from `start` ends in a cycle if `extra` is an obstacle as well.
+    /// Only the turning points are visited, so this is cheap enough to call for every cell.
+    #[must_use]
+    pub fn loops_with(&self, start: Pos, facing: Direction, extra: Option<Pos>) -> bool {
+        let mut turns = HashSet::new();
+        let (mut pos, mut facing) = (start, facing);
+
+        loop {
+            let Some(stop) = self.jump(pos, facing, extra) else {
+                return false;
+            };
+            if !turns.insert((stop, facing)) {
+                return true;
+            }
+            pos = stop;
+            facing = (self.turn)(facing);
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Walker;
+    use crate::grid::{Direction, Grid};
+
+    const LAB: &str = "\
+....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+";
+
+    fn walker() -> Walker {
+        Walker::new(Grid::parse(LAB, |c| c == '#'), Direction::turn_right)
+    }
+
+    #[test]
+    fn walks_until_leaving_the_grid() {
+        let walk = walker().walk((4, 6), Direction::Up);
+        assert!(!walk.looped);
+        assert_eq!(walk.visited().len(), 41);
+        assert_eq!(walk.path.last(), Some(&((7, 9), Direction::Down)));
+    }
+
+    #[test]
+    fn detects_loops_with_an_extra_obstacle() {
+        let walker = walker();
+        assert!(!walker.loops_with((4, 6), Direction::Up, None));
+        assert!(walker.loops_with((4, 6), Direction::Up, Some((3, 6))));
+        assert!(!walker.loops_with((4, 6), Direction::Up, Some((0, 0))));
+
+        let loops = walker
+            .walk((4, 6), Direction::Up)
+            .visited()
+            .into_iter()
+            .skip(1)
+            .filter(|&extra| walker.loops_with((4, 6), Direction::Up, Some(extra)))
+            .count();
+        assert_eq!(loops, 6);
+    }
+
+    #[test]
+    fn step_walk_reports_cycles() {
+        let walker = Walker::new(
+            Grid::parse(".#..\n...#\n#...\n..#.\n", |c| c == '#'),
+            Direction::turn_right,
+        );
+        assert!(walker.walk((1, 1), Direction::Up).looped);
+        assert!(walker.loops_with((1, 1), Direction::Up, None));
+    }
+}